@@ -0,0 +1,388 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{self, AtomicUsize};
+use std::thread;
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::board::Board;
+use crate::clock::TimeControl;
+use crate::game::Game;
+use crate::game::GameRecord;
+use crate::game::Outcome;
+use crate::game::Termination;
+use crate::player::Player;
+use crate::rating::EloEstimate;
+use crate::utils::ansi_for;
+use crate::utils::color;
+use crate::utils::invalid_data;
+
+pub struct Stat {
+    x_wins: u32,
+    draws: u32,
+    o_wins: u32,
+    iterations: u32,
+    timeouts: u32,
+    forfeits: u32,
+    nodes: u64,
+    duration: Duration,
+    description: String,
+}
+
+// How the games of a benchmark are played. Games start from the positions in turn,
+// or from the usual start if there are none.
+#[derive(Clone)]
+pub struct BenchConfig {
+    pub iterations: u32,
+    pub time_control: Option<TimeControl>,
+    pub positions: Vec<(Board, bool)>,
+}
+
+impl BenchConfig {
+    pub const fn new(iterations: u32) -> Self {
+        Self {
+            iterations,
+            time_control: None,
+            positions: Vec::new(),
+        }
+    }
+}
+
+// Reads starting positions, one per line as a compact board and the side to move.
+// Empty lines and lines starting with # are skipped.
+pub fn read_positions(path: impl AsRef<Path>) -> io::Result<Vec<(Board, bool)>> {
+    fs::read_to_string(path)?
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            Board::parse_position(line)
+                .ok_or_else(|| invalid_data(format!("invalid position: {line}")))
+        })
+        .collect()
+}
+
+impl Stat {
+    pub const fn new(
+        x_wins: u32,
+        draws: u32,
+        o_wins: u32,
+        iterations: u32,
+        duration: Duration,
+        description: String,
+    ) -> Self {
+        Self {
+            x_wins,
+            draws,
+            o_wins,
+            iterations,
+            timeouts: 0,
+            forfeits: 0,
+            nodes: 0,
+            duration,
+            description,
+        }
+    }
+
+    // How much stronger the X player is than the O player.
+    pub fn elo(&self) -> EloEstimate {
+        EloEstimate::new(self.x_wins, self.draws, self.o_wins)
+    }
+
+    fn add(&mut self, record: &GameRecord) {
+        let score = record.score();
+        self.iterations += 1;
+        self.nodes += record.nodes();
+        match score.outcome() {
+            Outcome::Xwin => self.x_wins += 1,
+            Outcome::Draw => self.draws += 1,
+            Outcome::OWin => self.o_wins += 1,
+        }
+        match score.termination() {
+            Termination::Finished => {}
+            Termination::Timeout(_) => self.timeouts += 1,
+            Termination::Forfeit(..) => self.forfeits += 1,
+        }
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    pub const fn x_wins(&self) -> u32 {
+        self.x_wins
+    }
+
+    pub const fn draws(&self) -> u32 {
+        self.draws
+    }
+
+    pub const fn o_wins(&self) -> u32 {
+        self.o_wins
+    }
+
+    pub const fn iterations(&self) -> u32 {
+        self.iterations
+    }
+
+    pub const fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    // Positions searched in all the games, by players that report it.
+    pub const fn nodes(&self) -> u64 {
+        self.nodes
+    }
+
+    pub const fn timeouts(&self) -> u32 {
+        self.timeouts
+    }
+
+    // Games lost by an illegal move or pass.
+    pub const fn forfeits(&self) -> u32 {
+        self.forfeits
+    }
+
+    pub fn print_header() {
+        println!(
+            "{:<22} | {:^28} | {:^6} | {:^8} | {:^8} | {:^22}",
+            "Description", "results", "runs", "total", "avg", "elo of X over O"
+        );
+    }
+
+    fn format_time(&self, duration: Duration) -> String {
+        let time = duration.as_nanos();
+        if time < 1000 {
+            format!("{time} ns")
+        } else if time < 1_000_000 {
+            format!("{:.1} μs", time as f64 / 1_000.0)
+        } else if time < 1_000_000_000 {
+            format!("{:.1} ms", time as f64 / 1_000_000.0)
+        } else {
+            format!("{:.1} s", duration.as_secs_f64())
+        }
+    }
+}
+
+impl fmt::Display for Stat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:<22} | {} {:>6}  {} {:>6}  D {:>6} | {:>6} | {:>8} | {:>8} | {:<22}",
+            self.description,
+            color("X", ansi_for('x')),
+            self.x_wins,
+            color("O", ansi_for('o')),
+            self.o_wins,
+            self.draws,
+            self.iterations,
+            self.format_time(self.duration),
+            self.format_time(self.duration / self.iterations),
+            self.elo().to_string(),
+        )?;
+        if self.timeouts > 0 {
+            write!(f, " | {} on time", self.timeouts)?;
+        }
+        if self.forfeits > 0 {
+            write!(f, " | {} forfeited", self.forfeits)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn run_bench<P1: Player, P2: Player>(
+    p1: P1,
+    p2: P2,
+    iterations: u32,
+    description: impl Into<String>,
+) -> Stat {
+    run_bench_with(p1, p2, &BenchConfig::new(iterations), description, |_| {})
+}
+
+// Same as run_bench with more settings, handing every finished game to on_game,
+// for example to export it.
+pub fn run_bench_with<P1: Player, P2: Player>(
+    p1: P1,
+    p2: P2,
+    config: &BenchConfig,
+    description: impl Into<String>,
+    mut on_game: impl FnMut(GameRecord),
+) -> Stat {
+    let start = Instant::now();
+    let mut stat = Stat::new(0, 0, 0, 0, Duration::ZERO, description.into());
+
+    let mut game = Game::new(p1, p2);
+    game.set_time_control(config.time_control);
+    for i in 0..config.iterations as usize {
+        let record = play_game(&mut game, config, i);
+        stat.add(&record);
+        on_game(record);
+    }
+
+    stat.duration = start.elapsed();
+    stat
+}
+
+// Plays pairs of games from the same position with colours swapped: a_x against b_o, then b_x
+// against a_o. The config's iterations count pairs, with a Stat for each colour assignment.
+pub fn run_bench_pairs<A1: Player, B1: Player, B2: Player, A2: Player>(
+    (a_x, b_o): (A1, B1),
+    (b_x, a_o): (B2, A2),
+    config: &BenchConfig,
+    descriptions: [&str; 2],
+    mut on_game: impl FnMut(GameRecord),
+) -> [Stat; 2] {
+    let [first, second] = descriptions.map(|d| Stat::new(0, 0, 0, 0, Duration::ZERO, d.into()));
+    let mut stats = [first, second];
+
+    let mut games = (Game::new(a_x, b_o), Game::new(b_x, a_o));
+    games.0.set_time_control(config.time_control);
+    games.1.set_time_control(config.time_control);
+    for i in 0..config.iterations as usize {
+        let start = Instant::now();
+        let record = play_game(&mut games.0, config, i);
+        stats[0].duration += start.elapsed();
+        stats[0].add(&record);
+        on_game(record);
+
+        let start = Instant::now();
+        let record = play_game(&mut games.1, config, i);
+        stats[1].duration += start.elapsed();
+        stats[1].add(&record);
+        on_game(record);
+    }
+    stats
+}
+
+// Same as run_bench_with, spreading the games over threads. Every game gets new players from
+// the factories, which are given the symbol and a seed derived from the master seed and the game
+// number, so the games and the results don't depend on the number of threads. on_game gets
+// the games in order once all are played.
+pub fn run_bench_parallel<P1: Player, P2: Player>(
+    make_x: impl Fn(char, u64) -> P1 + Sync,
+    make_o: impl Fn(char, u64) -> P2 + Sync,
+    config: &BenchConfig,
+    seed: u64,
+    threads: usize,
+    description: impl Into<String>,
+    mut on_game: impl FnMut(GameRecord),
+) -> Stat {
+    let start = Instant::now();
+    let mut stat = Stat::new(0, 0, 0, 0, Duration::ZERO, description.into());
+
+    let records = run_parallel(config.iterations as usize, threads, |i| {
+        let x = make_x('x', game_seed(seed, 2 * i));
+        let o = make_o('o', game_seed(seed, 2 * i + 1));
+        play_game(&mut new_game(x, o, config), config, i)
+    });
+    for record in records {
+        stat.add(&record);
+        on_game(record);
+    }
+
+    stat.duration = start.elapsed();
+    stat
+}
+
+// Same as run_bench_pairs, spreading the pairs over threads with players built as in
+// run_bench_parallel. make_a and make_b build the two sides of the match for either colour.
+pub fn run_bench_pairs_parallel<A: Player, B: Player>(
+    make_a: impl Fn(char, u64) -> A + Sync,
+    make_b: impl Fn(char, u64) -> B + Sync,
+    config: &BenchConfig,
+    seed: u64,
+    threads: usize,
+    descriptions: [&str; 2],
+    mut on_game: impl FnMut(GameRecord),
+) -> [Stat; 2] {
+    let start = Instant::now();
+    let [first, second] = descriptions.map(|d| Stat::new(0, 0, 0, 0, Duration::ZERO, d.into()));
+    let mut stats = [first, second];
+
+    // Even games have a as x, odd games b, two games in a row from the same position.
+    let records = run_parallel(2 * config.iterations as usize, threads, |game| {
+        let (x_seed, o_seed) = (game_seed(seed, 2 * game), game_seed(seed, 2 * game + 1));
+        let pair = game / 2;
+        if game % 2 == 0 {
+            let players = (make_a('x', x_seed), make_b('o', o_seed));
+            play_game(&mut new_game(players.0, players.1, config), config, pair)
+        } else {
+            let players = (make_b('x', x_seed), make_a('o', o_seed));
+            play_game(&mut new_game(players.0, players.1, config), config, pair)
+        }
+    });
+    for (game, record) in records.into_iter().enumerate() {
+        stats[game % 2].add(&record);
+        on_game(record);
+    }
+
+    // Both colour assignments ran side by side, the time is split between them.
+    let duration = start.elapsed();
+    for stat in &mut stats {
+        stat.duration = duration / 2;
+    }
+    stats
+}
+
+// A different seed for every game of a benchmark, by the SplitMix64 mixer.
+pub const fn game_seed(seed: u64, game: usize) -> u64 {
+    let mut z = seed.wrapping_add((game as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+fn new_game<P1: Player, P2: Player>(x: P1, o: P2, config: &BenchConfig) -> Game<P1, P2> {
+    let mut game = Game::new(x, o);
+    game.set_time_control(config.time_control);
+    game
+}
+
+// Plays the given number of games on a pool of threads taking the next game as they finish one,
+// returning the records in game order.
+fn run_parallel(
+    games: usize,
+    threads: usize,
+    play: impl Fn(usize) -> GameRecord + Sync,
+) -> Vec<GameRecord> {
+    let next = AtomicUsize::new(0);
+    let mut records: Vec<(usize, GameRecord)> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads.clamp(1, games.max(1)))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut played = Vec::new();
+                    loop {
+                        let game = next.fetch_add(1, atomic::Ordering::Relaxed);
+                        if game >= games {
+                            return played;
+                        }
+                        played.push((game, play(game)));
+                    }
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap())
+            .collect()
+    });
+    records.sort_by_key(|(game, _)| *game);
+    records.into_iter().map(|(_, record)| record).collect()
+}
+
+// Plays game number i of a benchmark, from the config's positions in turn.
+fn play_game<P1: Player, P2: Player>(
+    game: &mut Game<P1, P2>,
+    config: &BenchConfig,
+    i: usize,
+) -> GameRecord {
+    if !config.positions.is_empty() {
+        let (board, x_turn) = &config.positions[i % config.positions.len()];
+        game.set_start(board.clone(), *x_turn);
+    }
+    let record = game.run();
+    game.reset();
+    record
+}
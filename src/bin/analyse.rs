@@ -0,0 +1,145 @@
+use std::env;
+use std::process;
+
+use othello::board::Board;
+use othello::cli::Args;
+use othello::eval::EvalSpec;
+use othello::eval::Evaluator;
+use othello::eval::Term;
+use othello::ggf::GgfGame;
+use othello::transcript;
+
+const USAGE: &str = "Usage:
+    analyse [<board> <x|o>] [--moves <transcript>] [--eval simple|good|strategic|tapered|<config file>]
+    analyse --ggf <file> [--game <n>] [--ply <n>] [--eval ...]
+
+The board is written as 64 characters from a8 to h1 using x, o and -.
+Moves like f5d6c3 are played from the board, or from the start position without one.
+With --ggf the position after the given ply of a recorded game is analysed.";
+
+fn fail(msg: &str) -> ! {
+    eprintln!("{msg}");
+    eprintln!("{USAGE}");
+    process::exit(1);
+}
+
+fn load_eval(name: &str) -> Box<dyn Evaluator> {
+    EvalSpec::parse(name)
+        .build()
+        .unwrap_or_else(|err| fail(&format!("Could not load {name}: {err}")))
+}
+
+fn print_breakdown(terms: &[Term], total: i32) {
+    println!(
+        "{:<24} {:>8} {:>8} {:>12}",
+        "feature", "value", "weight", "contribution"
+    );
+    for term in terms {
+        println!(
+            "{:<24} {:>8.2} {:>8} {:>12.2}",
+            term.name, term.value, term.weight, term.contribution
+        );
+    }
+    println!("{:<24} {:>8} {:>8} {:>12}", "total", "", "", total);
+}
+
+// One row per legal move with the contribution of every feature after it is played.
+fn print_moves(eval: &dyn Evaluator, board: &Board, x_turn: bool) {
+    let mut rows = Vec::new();
+    let mut names: Vec<String> = Vec::new();
+    for mv in board.moves_iter(x_turn) {
+        let mut next = board.clone();
+        next.apply_move(mv, x_turn);
+        let terms = eval.explain(&next).unwrap_or_default();
+        for term in &terms {
+            if !names.contains(&term.name) {
+                names.push(term.name.clone());
+            }
+        }
+        rows.push((mv, eval.eval(&next), terms));
+    }
+
+    // Best move for the side to move first.
+    rows.sort_by_key(|&(_, score, _)| {
+        if x_turn {
+            -(score as i64)
+        } else {
+            score as i64
+        }
+    });
+
+    print!("{:<6} {:>12}", "move", "eval");
+    for name in &names {
+        print!(" {:>w$}", name, w = name.len().max(8));
+    }
+    println!();
+    for (mv, score, terms) in rows {
+        print!("{:<6} {:>12}", Board::square_name(mv), score);
+        for name in &names {
+            let width = name.len().max(8);
+            match terms.iter().find(|t| &t.name == name) {
+                Some(term) => print!(" {:>width$.1}", term.contribution),
+                None => print!(" {:>width$}", "-"),
+            }
+        }
+        println!();
+    }
+}
+
+// The position after some plies of a game from a GGF file.
+fn load_position(args: &Args, path: &str) -> (Board, bool) {
+    let games = GgfGame::read_file(path)
+        .unwrap_or_else(|err| fail(&format!("Could not load {path}: {err}")));
+    let index: usize = args.value("game", 0).unwrap_or_else(|err| fail(&err));
+    let ply: usize = args.value("ply", 0).unwrap_or_else(|err| fail(&err));
+    let Some(game) = games.get(index) else {
+        fail(&format!("{path} has {} games", games.len()));
+    };
+    game.record
+        .position_at(ply)
+        .unwrap_or_else(|| fail(&format!("The game has {} plies", game.record.moves().len())))
+}
+
+fn main() {
+    let args = Args::parse(env::args().skip(1)).unwrap_or_else(|err| fail(&err));
+    let (board, x_turn) = match args.positional()[..] {
+        [] if args.get("ggf").is_some() => load_position(&args, args.get("ggf").unwrap()),
+        [] => (Board::new(), true),
+        [board, side] => {
+            let board = Board::from_compact(board).unwrap_or_else(|| fail("Invalid board"));
+            match side {
+                "x" => (board, true),
+                "o" => (board, false),
+                _ => fail("Side to move must be x or o"),
+            }
+        }
+        _ => fail("Expected a board and a side to move"),
+    };
+    let (board, x_turn) = match args.get("moves") {
+        Some(text) => {
+            let record = transcript::parse_moves(text)
+                .and_then(|moves| transcript::replay(&board, x_turn, &moves))
+                .unwrap_or_else(|err| fail(&format!("Invalid transcript, {err}")));
+            record.positions().pop().unwrap()
+        }
+        None => (board, x_turn),
+    };
+    let eval = load_eval(args.get("eval").unwrap_or("good"));
+
+    board.print(x_turn);
+    println!("{} to move", if x_turn { "X" } else { "O" });
+    println!();
+
+    let total = eval.eval(&board);
+    match eval.explain(&board) {
+        Some(terms) => print_breakdown(&terms, total),
+        None => println!("Evaluation {total} (no breakdown available)"),
+    }
+    println!();
+
+    if board.num_moves(x_turn) == 0 {
+        println!("No legal moves");
+        return;
+    }
+    print_moves(eval.as_ref(), &board, x_turn);
+}
@@ -0,0 +1,62 @@
+use std::env;
+use std::fs;
+use std::process;
+
+use othello::cli::Args;
+use othello::eval::EvalSpec;
+use othello::openings;
+use othello::player::Negamax;
+use othello::player::Player;
+
+fn fail(msg: &str) -> ! {
+    eprintln!("{msg}");
+    eprintln!(
+        "Usage: openings <out> [--count <n>] [--moves <n>] [--depth <d>] [--eval <evaluator>]
+                [--max-eval <score>] [--seed <s>]
+
+Writes random openings whose position a search at the given depth scores within max-eval,
+one transcript per line."
+    );
+    process::exit(1);
+}
+
+fn flag<T: std::str::FromStr>(args: &Args, name: &str, default: T) -> T {
+    args.value(name, default).unwrap_or_else(|err| fail(&err))
+}
+
+fn main() {
+    let args = Args::parse(env::args().skip(1)).unwrap_or_else(|err| fail(&err));
+    let [out] = args.positional()[..] else {
+        fail("Expected an output file");
+    };
+    let count: usize = flag(&args, "count", 100);
+    let moves: usize = flag(&args, "moves", 8);
+    let depth: u8 = flag(&args, "depth", 6);
+    let max_eval: i32 = flag(&args, "max-eval", 4);
+    let seed: u64 = flag(&args, "seed", 0x_A142_3141_A150_4411);
+    let eval = EvalSpec::parse(args.get("eval").unwrap_or("good"));
+
+    let build = |symbol| {
+        let eval = eval
+            .build()
+            .unwrap_or_else(|err| fail(&format!("Could not load {eval}: {err}")));
+        Negamax::new(symbol, depth, eval)
+    };
+    let (mut x, mut o) = (build('x'), build('o'));
+    let suite = openings::generate(count, moves, seed, max_eval, |board, x_turn| {
+        let player = if x_turn { &mut x } else { &mut o };
+        player.get_move(board.clone())?;
+        player.last_eval()
+    });
+    if suite.len() < count {
+        eprintln!("Only found {} balanced openings", suite.len());
+    }
+
+    let text = format!(
+        "# {moves} move openings scored within {max_eval} by negamax {depth} {eval}\n{}\n",
+        suite.join("\n")
+    );
+    if let Err(err) = fs::write(out, text) {
+        fail(&format!("Could not write {out}: {err}"));
+    }
+}
@@ -0,0 +1,85 @@
+use std::env;
+use std::io::{self, Write};
+use std::process;
+
+use othello::bench;
+use othello::cli::Args;
+use othello::openings;
+use othello::player::PlayerSpec;
+use othello::rating::EloEstimate;
+use othello::sprt::{Decision, Sprt, SprtRun};
+
+fn fail(msg: &str) -> ! {
+    eprintln!("{msg}");
+    eprintln!(
+        "Usage: sprt <candidate> <baseline> [--elo0 <elo>] [--elo1 <elo>] [--alpha <p>] [--beta <p>]
+            [--max-pairs <n>] [--time <control>] [--positions <file>] [--openings <file>]
+
+Players are written like negamax 6 good or negamax 6 presets/good.txt.
+Tests H0, the candidate is elo0 (default 0) stronger, against H1, it is elo1 (default 10) stronger."
+    );
+    process::exit(1);
+}
+
+fn main() {
+    let args = Args::parse(env::args().skip(1)).unwrap_or_else(|err| fail(&err));
+    let [candidate, baseline] = args.positional()[..] else {
+        fail("Expected a candidate and a baseline");
+    };
+    let spec = |text: &str| PlayerSpec::parse(text).unwrap_or_else(|err| fail(&err));
+    let value = |name, default| args.value(name, default).unwrap_or_else(|err| fail(&err));
+
+    let sprt = Sprt::new(
+        value("elo0", 0.0),
+        value("elo1", 10.0),
+        value("alpha", 0.05),
+        value("beta", 0.05),
+    );
+    let mut run = SprtRun::new(spec(candidate), spec(baseline), sprt);
+    run.max_pairs = args
+        .value("max-pairs", run.max_pairs)
+        .unwrap_or_else(|err| fail(&err));
+    run.time_control = args
+        .get("time")
+        .map(|control| control.parse().unwrap_or_else(|err: String| fail(&err)));
+    if let Some(path) = args.get("positions") {
+        run.positions = bench::read_positions(path)
+            .unwrap_or_else(|err| fail(&format!("Could not read {path}: {err}")));
+    }
+    if let Some(path) = args.get("openings") {
+        run.positions.extend(
+            openings::read_openings(path)
+                .unwrap_or_else(|err| fail(&format!("Could not read {path}: {err}"))),
+        );
+    }
+
+    let (lower, upper) = sprt.bounds();
+    println!(
+        "{} vs {}, H0 {} H1 {}, accepting at LLR {lower:.2} or {upper:.2}",
+        run.candidate, run.baseline, sprt.elo0, sprt.elo1
+    );
+    let (decision, tally) = run
+        .run(|tally, llr| {
+            // Rewrites the same line, so the LLR can be watched as the games come in.
+            print!(
+                "\rgames {:>6} | W {:>5} D {:>5} L {:>5} | LLR {llr:>6.2} | {}    ",
+                tally.games(),
+                tally.wins,
+                tally.draws,
+                tally.losses,
+                EloEstimate::new(tally.wins, tally.draws, tally.losses)
+            );
+            let _ = io::stdout().flush();
+        })
+        .unwrap_or_else(|err| fail(&format!("Could not create the players: {err}")));
+    println!();
+
+    match decision {
+        Decision::AcceptH0 => println!(
+            "H0 accepted: the candidate is no more than {} Elo stronger",
+            sprt.elo0
+        ),
+        Decision::AcceptH1 => println!("H1 accepted: the candidate is {} Elo stronger", sprt.elo1),
+        Decision::Continue => println!("No decision after {} games", tally.games()),
+    }
+}
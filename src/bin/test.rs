@@ -1,151 +1,372 @@
-use std::fmt;
-use std::time::Duration;
-use std::time::Instant;
-
-use othello::eval::GoodEval;
-use othello::eval::SimpleEval;
-use othello::game::Game;
-use othello::game::Outcome;
-use othello::player::AlphaBeta;
-use othello::player::Minimax;
+use std::env;
+use std::fs;
+use std::fs::File;
+use std::io::BufWriter;
+use std::io::Write;
+use std::process;
+use std::thread;
+
+use othello::bench;
+use othello::bench::BenchConfig;
+use othello::bench::Stat;
+use othello::bench::run_bench_pairs_parallel;
+use othello::bench::run_bench_parallel;
+use othello::board::Board;
+use othello::cli::Args;
+use othello::clock::TimeControl;
+use othello::eval::ConfigEval;
+use othello::game::GameRecord;
+use othello::ggf::GgfGame;
+use othello::openings;
 use othello::player::Mixed;
 use othello::player::Negamax;
-use othello::player::Player;
-use othello::player::RandomAI;
-use othello::utils::ansi_for;
-use othello::utils::color;
-pub struct Stat {
-    x_wins: u32,
-    draws: u32,
-    o_wins: u32,
-    iterations: u32,
-    duration: Duration,
-    description: String,
+use othello::player::PlayerSpec;
+use othello::report::{self, Fingerprint, GameSummary, Matchup, Report};
+use othello::suite;
+
+fn load_config(path: &str) -> ConfigEval {
+    ConfigEval::from_file(path).unwrap_or_else(|err| {
+        eprintln!("Could not load {path}: {err}");
+        process::exit(1);
+    })
+}
+
+// The benchmarks run without arguments.
+const DEFAULT_SUITE: &str = include_str!("../../suites/default.txt");
+
+fn fail(msg: &str) -> ! {
+    eprintln!("{msg}");
+    eprintln!(
+        "Usage: test [<config a> <config b>] [--ggf <file>] [--time <control>]
+            [--suite <file> | --x <player> --o <player> [--games <n>] [--name <text>]]
+            [--filter <text>]
+            [--positions <file>] [--openings <file>] [--threads <n>]
+            [--json <file>] [--csv <file>]
+       test compare <baseline csv> <new csv> [--score <points>] [--speed <percent>]"
+    );
+    process::exit(1);
+}
+
+// Settings shared by every benchmark of a run.
+struct Run {
+    time_control: Option<TimeControl>,
+    positions: Vec<(Board, bool)>,
+    // Whether the positions include openings, which are played in colour-swapped pairs.
+    openings: bool,
+    ggf: Option<BufWriter<File>>,
+    seed: u64,
+    threads: usize,
+    // Kept for the JSON or CSV report when one is asked for.
+    matchups: Option<Vec<Matchup>>,
 }
 
-impl Stat {
-    pub const fn new(
-        x_wins: u32,
-        draws: u32,
-        o_wins: u32,
-        iterations: u32,
-        duration: Duration,
-        description: String,
-    ) -> Self {
-        Self {
-            x_wins,
-            draws,
-            o_wins,
+impl Run {
+    fn config(&self, iterations: u32) -> BenchConfig {
+        BenchConfig {
             iterations,
-            duration,
-            description,
+            time_control: self.time_control,
+            positions: self.positions.clone(),
         }
     }
 
-    fn print_header() {
-        println!(
-            "{:<22} | {:^28} | {:^6} | {:^8} | {:^5}",
-            "Description", "results", "runs", "total", "avg"
-        );
+    // Writes the game to the GGF file if there is one and keeps it for the report.
+    fn write_game(
+        &mut self,
+        record: GameRecord,
+        black: &str,
+        white: &str,
+        time_control: Option<TimeControl>,
+        games: &mut Vec<GameSummary>,
+    ) {
+        if self.matchups.is_some() {
+            games.push(GameSummary::new(&record));
+        }
+        if let Some(writer) = &mut self.ggf {
+            let mut game = GgfGame::from_record(record, black, white);
+            if let Some(control) = time_control {
+                game.time_control = control.to_string();
+            }
+            if let Err(err) = writeln!(writer, "{game}") {
+                fail(&format!("Could not write games: {err}"));
+            }
+        }
     }
 
-    fn format_time(&self, duration: Duration) -> String {
-        let time = duration.as_nanos();
-        if time < 1000 {
-            format!("{time} ns")
-        } else if time < 1_000_000 {
-            format!("{:.1} μs", time as f64 / 1_000.0)
-        } else if time < 1_000_000_000 {
-            format!("{:.1} ms", time as f64 / 1_000_000.0)
-        } else {
-            format!("{:.1} s", duration.as_secs_f64())
+    fn finish(&mut self, stat: Stat, games: Vec<GameSummary>) {
+        println!("{stat}");
+        if let Some(matchups) = &mut self.matchups {
+            matchups.push(Matchup { stat, games });
         }
     }
 }
 
-impl fmt::Display for Stat {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "{:<22} | {} {:>6}  {} {:>6}  D {:>6} | {:>6} | {:>8} | {:>5}",
-            self.description,
-            color("X", ansi_for('x')),
-            self.x_wins,
-            color("O", ansi_for('o')),
-            self.o_wins,
-            self.draws,
-            self.iterations,
-            self.format_time(self.duration),
-            self.format_time(self.duration / self.iterations),
-        )
+// Runs a matchup of a suite on the run's threads and prints it, writing every game to the GGF
+// file if there is one. Every game builds its players from the specs with its own seed. Player
+// names for the file come from the description, split at " vs ". With openings the games are
+// played in pairs from the same opening with colours swapped, printed as two rows.
+fn bench(matchup: &suite::Matchup, run: &mut Run) {
+    let description = &matchup.description;
+    let make = |spec: &PlayerSpec| {
+        let spec = spec.clone();
+        move |symbol, seed| {
+            spec.build_seeded(symbol, seed)
+                .unwrap_or_else(|err| fail(&format!("Could not build {spec}: {err}")))
+        }
+    };
+
+    let mut config = run.config(matchup.games);
+    if matchup.time_control.is_some() {
+        config.time_control = matchup.time_control;
+    }
+    let mut paired = run.openings;
+    if matchup.positions.is_some() || matchup.openings.is_some() {
+        config.positions = matchup
+            .read_positions()
+            .unwrap_or_else(|err| fail(&format!("{description}: {err}")));
+        paired = matchup.openings.is_some();
+    }
+    let seed = matchup.seed.unwrap_or(run.seed);
+    let (black, white) = description.split_once(" vs ").unwrap_or(("x", "o"));
+
+    if paired {
+        config.iterations = matchup.games.div_ceil(2);
+        let swapped = format!("{white} vs {black}");
+        let mut games = [Vec::new(), Vec::new()];
+        let mut played = 0;
+        let stats = run_bench_pairs_parallel(
+            make(&matchup.x),
+            make(&matchup.o),
+            &config,
+            seed,
+            run.threads,
+            [description, &swapped],
+            |record| {
+                let (black, white) = if played % 2 == 0 {
+                    (black, white)
+                } else {
+                    (white, black)
+                };
+                let games = &mut games[played % 2];
+                run.write_game(record, black, white, config.time_control, games);
+                played += 1;
+            },
+        );
+        for (stat, games) in stats.into_iter().zip(games) {
+            run.finish(stat, games);
+        }
+    } else {
+        let mut games = Vec::new();
+        let stat = run_bench_parallel(
+            make(&matchup.x),
+            make(&matchup.o),
+            &config,
+            seed,
+            run.threads,
+            description,
+            |record| run.write_game(record, black, white, config.time_control, &mut games),
+        );
+        run.finish(stat, games);
     }
 }
 
-pub fn run_bench<P1: Player, P2: Player>(
-    p1: P1,
-    p2: P2,
-    iterations: u32,
-    description: impl Into<String>,
-) -> Stat {
-    let start = Instant::now();
-
-    let mut x_wins: u32 = 0;
-    let mut draws: u32 = 0;
-    let mut o_wins: u32 = 0;
-
-    let mut game = Game::new(p1, p2);
-    for _ in 0..iterations {
-        match game.run(false).outcome() {
-            Outcome::Xwin => x_wins += 1,
-            Outcome::Draw => draws += 1,
-            Outcome::OWin => o_wins += 1,
+// Plays two evaluator configs against each other in pairs of games from the same position
+// with colours swapped. With a time control the clock limits the search instead of a fixed depth.
+fn ab_test(path_a: &str, path_b: &str, run: &mut Run) {
+    const PAIRS: u32 = 50;
+    let depth = if run.time_control.is_some() { 60 } else { 4 };
+    let player = |eval: ConfigEval| {
+        move |symbol, seed| Mixed::new(Negamax::new(symbol, depth, eval.clone()), symbol, seed, 0.1)
+    };
+
+    let config = run.config(PAIRS);
+    let mut games = [Vec::new(), Vec::new()];
+    let mut played = 0;
+    let [ab, ba] = run_bench_pairs_parallel(
+        player(load_config(path_a)),
+        player(load_config(path_b)),
+        &config,
+        run.seed,
+        run.threads,
+        ["A vs B", "B vs A"],
+        |record| {
+            // Games alternate between the two colour assignments.
+            let (black, white) = if played % 2 == 0 {
+                ("A", "B")
+            } else {
+                ("B", "A")
+            };
+            run.write_game(
+                record,
+                black,
+                white,
+                config.time_control,
+                &mut games[played % 2],
+            );
+            played += 1;
+        },
+    );
+    let [ab_games, ba_games] = games;
+    run.finish(ab, ab_games);
+    run.finish(ba, ba_games);
+}
+
+// Compares a CSV report against a baseline and fails if X's score or the speed regressed.
+fn compare(baseline: &str, new: &str, args: &Args) {
+    let read = |path: &str| {
+        fs::read_to_string(path)
+            .and_then(|text| report::read_csv(&text))
+            .unwrap_or_else(|err| fail(&format!("Could not read {path}: {err}")))
+    };
+    let (old_machine, old) = read(baseline);
+    let (new_machine, new) = read(new);
+    if old_machine != new_machine {
+        println!("Warning: the runs are from different machines");
+        println!("  baseline: {}", old_machine.unwrap_or_default());
+        println!("  new:      {}", new_machine.unwrap_or_default());
+    }
+
+    let score = args.value("score", 5.0).unwrap_or_else(|err| fail(&err));
+    let speed = args.value("speed", 10.0).unwrap_or_else(|err| fail(&err));
+    let comparisons = report::compare(&old, &new, score, speed);
+    println!(
+        "{:<22} | {:>12} | {:>12}",
+        "Matchup", "X score", "time/game"
+    );
+    let mut regressions = 0;
+    for comparison in &comparisons {
+        let flag = |regressed| if regressed { " !" } else { "" };
+        println!(
+            "{:<22} | {:>+9.1} pp{} | {:>+9.1} %{}",
+            comparison.matchup,
+            comparison.score_change,
+            flag(comparison.score_regression),
+            comparison.time_change,
+            flag(comparison.speed_regression)
+        );
+        regressions += comparison.score_regression as u32 + comparison.speed_regression as u32;
+    }
+    if regressions > 0 {
+        println!("{regressions} regressions beyond {score} points or {speed}% slower");
+        process::exit(1);
+    }
+}
+
+// The matchups to run: the one given by --x and --o, the suite file or the default suite,
+// keeping those whose description contains --filter.
+fn matchups(args: &Args) -> Vec<suite::Matchup> {
+    let matchups = match (args.get("x"), args.get("o")) {
+        (Some(x), Some(o)) => {
+            let spec = |text| PlayerSpec::parse(text).unwrap_or_else(|err| fail(&err));
+            let name = args
+                .get("name")
+                .map_or(format!("{x} vs {o}"), str::to_string);
+            let games = args.value("games", 100).unwrap_or_else(|err| fail(&err));
+            if games == 0 {
+                fail("--games needs at least 1 game");
+            }
+            vec![suite::Matchup::new(name, spec(x), spec(o), games)]
+        }
+        (None, None) => match args.get("suite") {
+            Some(path) => suite::read_file(path)
+                .unwrap_or_else(|err| fail(&format!("Could not read {path}: {err}"))),
+            None => suite::parse(DEFAULT_SUITE).unwrap(),
+        },
+        _ => fail("Expected both --x and --o"),
+    };
+    let matchups = match args.get("filter") {
+        Some(filter) => suite::filter(matchups, filter),
+        None => matchups,
+    };
+    if matchups.is_empty() {
+        fail("No matchup matches the filter");
+    }
+    // Fails before any game is played if an evaluator can't be loaded.
+    for matchup in &matchups {
+        for spec in [&matchup.x, &matchup.o] {
+            if let Err(err) = spec.build('x') {
+                let description = &matchup.description;
+                fail(&format!("{description}: could not build {spec}: {err}"));
+            }
         }
-        game.reset();
-    }
-
-    let duration = start.elapsed();
-    Stat::new(
-        x_wins,
-        draws,
-        o_wins,
-        iterations,
-        duration,
-        description.into(),
-    )
+    }
+    matchups
 }
 
 fn main() {
-    // naming scheme - algorithm_symbol_depth_evaluator
     const SEED: u64 = 0x_A142_3141_A150_4411;
-    Stat::print_header();
-
-    let random_o = RandomAI::with_seed('o', SEED);
-    let random_x = RandomAI::with_seed('x', SEED);
-    let stat = run_bench(random_x, random_o, 100000, "Random vs random");
-    println!("{stat}");
-
-    let mini_x_s_4 = Minimax::new('x', 4, SimpleEval {});
-    let random_o = RandomAI::with_seed('o', SEED);
-    let stat = run_bench(mini_x_s_4, random_o, 200, "Mini 4s vs random");
-    println!("{stat}");
-
-    let alpha_x_s_6 = AlphaBeta::new('x', 6, SimpleEval {});
-    let random_o = RandomAI::with_seed('o', SEED);
-    let stat = run_bench(alpha_x_s_6, random_o, 100, "Alpha 6s vs random");
-    println!("{stat}");
-
-    let nega_x_s_6 = Negamax::new('x', 6, SimpleEval {});
-    let random_o = RandomAI::with_seed('o', SEED);
-    let stat = run_bench(nega_x_s_6, random_o, 100, "Nega 6s vs random");
-    println!("{stat}");
-
-    let nega_x_b_5 = Negamax::new('x', 5, GoodEval {});
-    let random_o = RandomAI::with_seed('o', SEED);
-    let stat = run_bench(nega_x_b_5, random_o, 100, "Nega 5B vs random");
-    println!("{stat}");
-
-    let nega_x_b_5 = Negamax::new('x', 3, GoodEval {});
-    let mixed_50_nega_s_5 = Mixed::new(Negamax::new('o', 8, SimpleEval {}), 'o', SEED, 0.2);
-    let stat = run_bench(nega_x_b_5, mixed_50_nega_s_5, 50, "Nega 5B vs 20% nega_9S");
-    println!("{stat}");
+    let args = Args::parse(env::args().skip(1)).unwrap_or_else(|err| fail(&err));
+    let time_control = args
+        .get("time")
+        .map(|control| control.parse().unwrap_or_else(|err: String| fail(&err)));
+    let ggf = args.get("ggf").map(|path| match File::create(path) {
+        Ok(file) => BufWriter::new(file),
+        Err(err) => fail(&format!("Could not create {path}: {err}")),
+    });
+    let mut positions = args.get("positions").map_or(Vec::new(), |path| {
+        bench::read_positions(path)
+            .unwrap_or_else(|err| fail(&format!("Could not read {path}: {err}")))
+    });
+    if let Some(path) = args.get("openings") {
+        positions.extend(
+            openings::read_openings(path)
+                .unwrap_or_else(|err| fail(&format!("Could not read {path}: {err}"))),
+        );
+    }
+    let threads = args
+        .value(
+            "threads",
+            thread::available_parallelism().map_or(1, |threads| threads.get()),
+        )
+        .unwrap_or_else(|err| fail(&err));
+    let mut run = Run {
+        time_control,
+        positions,
+        openings: args.get("openings").is_some(),
+        ggf,
+        seed: SEED,
+        threads,
+        matchups: (args.get("json").is_some() || args.get("csv").is_some()).then(Vec::new),
+    };
+
+    if let ["compare", baseline, new] = args.positional()[..] {
+        compare(baseline, new, &args);
+        return;
+    }
+
+    match args.positional()[..] {
+        [] => {
+            let matchups = matchups(&args);
+            Stat::print_header();
+            for matchup in &matchups {
+                bench(matchup, &mut run);
+            }
+        }
+        [path_a, path_b] => {
+            Stat::print_header();
+            ab_test(path_a, path_b, &mut run)
+        }
+        _ => fail("Expected two configs or none"),
+    }
+    if let Some(Err(err)) = run.ggf.as_mut().map(BufWriter::flush) {
+        fail(&format!("Could not write games: {err}"));
+    }
+
+    if let Some(matchups) = run.matchups.take() {
+        let report = Report {
+            machine: Fingerprint::current(),
+            seed: run.seed,
+            threads: run.threads,
+            matchups,
+        };
+        for (flag, text) in [
+            ("json", Report::to_json as fn(&Report) -> String),
+            ("csv", Report::to_csv),
+        ] {
+            if let Some(path) = args.get(flag)
+                && let Err(err) = fs::write(path, text(&report))
+            {
+                fail(&format!("Could not write {path}: {err}"));
+            }
+        }
+    }
 }
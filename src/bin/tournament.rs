@@ -0,0 +1,112 @@
+use std::env;
+use std::process;
+
+use othello::bench;
+use othello::cli::Args;
+use othello::openings;
+use othello::rating::{EloEstimate, format_elo};
+use othello::tournament::{self, Format, Results, Tournament};
+
+fn fail(msg: &str) -> ! {
+    eprintln!("{msg}");
+    eprintln!(
+        "Usage: tournament <entrants file> [--format round-robin|gauntlet] [--pairs <n>]
+                  [--time <control>] [--positions <file>] [--openings <file>] [--out <results file>]
+       tournament --show <results file>
+
+Entrants are one per line as a unique name and a player, like good negamax 6 good.
+A gauntlet plays the first entrant against each of the others."
+    );
+    process::exit(1);
+}
+
+// The cross table, then the entrants by rating with their performance against their opponents.
+fn print_summary(results: &Results) {
+    let table = results.cross_table();
+    print!("{table}");
+    println!();
+
+    let ratings = results.ratings();
+    let mut order: Vec<usize> = (0..results.entrants.len()).collect();
+    order.sort_by(|&a, &b| ratings[b].total_cmp(&ratings[a]));
+    println!(
+        "{:<16} | {:>6} | {:>5} | {:>6} | performance",
+        "Entrant", "rating", "games", "score"
+    );
+    for idx in order {
+        let total = table.total(idx);
+        let performance = EloEstimate::new(total.wins, total.draws, total.losses);
+        println!(
+            "{:<16} | {:>6} | {:>5} | {:>5.1}% | {performance}",
+            results.entrants[idx].name,
+            format_elo(ratings[idx]),
+            total.games(),
+            100.0 * total.score() / total.games().max(1) as f64
+        );
+    }
+}
+
+fn main() {
+    let args = Args::parse(env::args().skip(1)).unwrap_or_else(|err| fail(&err));
+
+    if let Some(path) = args.get("show") {
+        let results = Results::from_file(path)
+            .unwrap_or_else(|err| fail(&format!("Could not read {path}: {err}")));
+        print_summary(&results);
+        return;
+    }
+
+    let [path] = args.positional()[..] else {
+        fail("Expected an entrants file");
+    };
+    let entrants = tournament::read_entrants(path)
+        .unwrap_or_else(|err| fail(&format!("Could not read {path}: {err}")));
+    if entrants.len() < 2 {
+        fail("A tournament needs at least two entrants");
+    }
+    let format = args
+        .value("format", Format::RoundRobin)
+        .unwrap_or_else(|err| fail(&err));
+    let pairs = args.value("pairs", 10).unwrap_or_else(|err| fail(&err));
+
+    let mut tournament = Tournament::new(entrants, format, pairs);
+    tournament.time_control = args
+        .get("time")
+        .map(|control| control.parse().unwrap_or_else(|err: String| fail(&err)));
+    if let Some(path) = args.get("positions") {
+        tournament.positions = bench::read_positions(path)
+            .unwrap_or_else(|err| fail(&format!("Could not read {path}: {err}")));
+    }
+    if let Some(path) = args.get("openings") {
+        tournament.positions.extend(
+            openings::read_openings(path)
+                .unwrap_or_else(|err| fail(&format!("Could not read {path}: {err}"))),
+        );
+    }
+
+    let total = tournament.pairings().len() * pairs as usize * 2;
+    let out = args.get("out");
+    let results = tournament
+        .run(|results| {
+            let game = &results.games[results.games.len() - 1];
+            let name = |idx: usize| &results.entrants[idx].name;
+            println!(
+                "{:>4}/{total} {} vs {} {}-{}",
+                results.games.len(),
+                name(game.x),
+                name(game.o),
+                game.score.x(),
+                game.score.o()
+            );
+            // Saved after every game, so an interrupted tournament keeps what it played.
+            if let Some(path) = out
+                && let Err(err) = results.save(path)
+            {
+                fail(&format!("Could not write {path}: {err}"));
+            }
+        })
+        .unwrap_or_else(|err| fail(&format!("Could not create the players: {err}")));
+
+    println!();
+    print_summary(&results);
+}
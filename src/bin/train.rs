@@ -0,0 +1,115 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::process;
+
+use othello::bench::Stat;
+use othello::bench::run_bench;
+use othello::cli::Args;
+use othello::eval::ConfigEval;
+use othello::eval::GoodEval;
+use othello::eval::PatternEval;
+use othello::eval::TaperedEval;
+use othello::eval::TdTrainer;
+use othello::eval::Tunable;
+use othello::player::Mixed;
+use othello::player::Negamax;
+
+const USAGE: &str = "Usage:
+    train <out dir> [--eval config|tapered|pattern] [--init <weights>] [--games <n>]
+          [--checkpoint <n>] [--depth <d>] [--lambda <l>] [--rate <r>] [--explore <p>]
+          [--bench <games>] [--seed <s>]";
+
+fn fail(msg: &str) -> ! {
+    eprintln!("{msg}");
+    eprintln!("{USAGE}");
+    process::exit(1);
+}
+
+fn flag<T: std::str::FromStr>(args: &Args, name: &str, default: T) -> T {
+    args.value(name, default).unwrap_or_else(|err| fail(&err))
+}
+
+// Plays the latest checkpoint against the previous one with both colours.
+fn bench_checkpoint<E: Tunable + Clone>(new: &E, old: &E, depth: u8, games: u32, seed: u64) {
+    let new_x = Mixed::new(Negamax::new('x', depth, new.clone()), 'x', seed, 0.1);
+    let old_o = Mixed::new(Negamax::new('o', depth, old.clone()), 'o', !seed, 0.1);
+    println!("{}", run_bench(new_x, old_o, games, "New vs previous"));
+
+    let old_x = Mixed::new(Negamax::new('x', depth, old.clone()), 'x', seed, 0.1);
+    let new_o = Mixed::new(Negamax::new('o', depth, new.clone()), 'o', !seed, 0.1);
+    println!("{}", run_bench(old_x, new_o, games, "Previous vs new"));
+}
+
+fn train<E: Tunable + Clone>(eval: E, args: &Args, out: &Path, extension: &str) {
+    let games: u64 = flag(args, "games", 1000);
+    let checkpoint: u64 = flag(args, "checkpoint", 100);
+    let depth: u8 = flag(args, "depth", 2);
+    let bench_games: u32 = flag(args, "bench", 20);
+    let seed: u64 = flag(args, "seed", 0x_A142_3141_A150_4411);
+
+    let mut trainer = TdTrainer::new(
+        eval,
+        flag(args, "lambda", 0.7),
+        flag(args, "rate", 0.0001),
+        depth,
+        flag(args, "explore", 0.1),
+    );
+    let mut previous = trainer.eval().clone();
+    let mut total_result = 0.0;
+
+    while trainer.games() < games {
+        total_result += trainer.train_game(seed.wrapping_add(trainer.games()));
+
+        if trainer.games().is_multiple_of(checkpoint) || trainer.games() == games {
+            let path = out.join(format!("checkpoint_{}.{extension}", trainer.games()));
+            if let Err(err) = trainer.eval().save_weights(&path) {
+                fail(&format!("Could not write {}: {err}", path.display()));
+            }
+            println!(
+                "{} games, average result for x {:.2}, saved {}",
+                trainer.games(),
+                total_result / trainer.games() as f64,
+                path.display()
+            );
+
+            Stat::print_header();
+            bench_checkpoint(trainer.eval(), &previous, depth, bench_games, seed);
+            println!();
+            previous = trainer.eval().clone();
+        }
+    }
+}
+
+fn loaded<E>(eval: io::Result<E>) -> E {
+    eval.unwrap_or_else(|err| fail(&format!("Could not load weights: {err}")))
+}
+
+fn main() {
+    let args = Args::parse(env::args().skip(1)).unwrap_or_else(|err| fail(&err));
+    let [out] = args.positional()[..] else {
+        fail("Expected an output directory");
+    };
+    let out = Path::new(out);
+    if let Err(err) = fs::create_dir_all(out) {
+        fail(&format!("Could not create {}: {err}", out.display()));
+    }
+
+    let init = args.get("init");
+    match args.get("eval").unwrap_or("config") {
+        "config" => {
+            let eval = init.map_or(Ok(GoodEval::default()), ConfigEval::from_file);
+            train(loaded(eval), &args, out, "txt");
+        }
+        "tapered" => {
+            let eval = init.map_or(Ok(TaperedEval::default()), TaperedEval::from_file);
+            train(loaded(eval), &args, out, "txt");
+        }
+        "pattern" => {
+            let eval = init.map_or(Ok(PatternEval::default()), PatternEval::from_file);
+            train(loaded(eval), &args, out, "bin");
+        }
+        _ => fail("Unknown evaluator"),
+    }
+}
@@ -0,0 +1,230 @@
+use std::env;
+use std::path::Path;
+use std::process;
+
+use othello::board::Board;
+use othello::cli::Args;
+use othello::eval::ConfigEval;
+use othello::eval::GoodEval;
+use othello::eval::PatternEval;
+use othello::eval::TaperedEval;
+use othello::eval::Tunable;
+use othello::eval::nn;
+use othello::eval::tune::{self, Sample};
+use othello::player::Mixed;
+use othello::player::Negamax;
+use othello::player::Player;
+use othello::wthor::WthorDatabase;
+
+const USAGE: &str = "Usage:
+    tune fit <dataset> <out> [--eval config|tapered|pattern] [--method ls|gd] [--init <weights>]
+             [--epochs <n>] [--rate <r>] [--scale <s>]
+    tune generate <out> <games> [--depth <d>] [--seed <s>]
+    tune export <dataset> <out>
+    tune wthor <out> <wtb file>... [--theoretical true]";
+
+fn fail(msg: &str) -> ! {
+    eprintln!("{msg}");
+    eprintln!("{USAGE}");
+    process::exit(1);
+}
+
+fn flag<T: std::str::FromStr>(args: &Args, name: &str, default: T) -> T {
+    args.value(name, default).unwrap_or_else(|err| fail(&err))
+}
+
+// The method defaults to least squares, or gradient descent for evaluators with too many weights.
+fn fit<E: Tunable>(mut eval: E, samples: &[Sample], args: &Args, out: &str) {
+    println!(
+        "Initial error: {:.3}",
+        tune::mean_squared_error(&eval, samples)
+    );
+    let method = if eval.num_weights() > tune::MAX_LEAST_SQUARES_WEIGHTS {
+        "gd"
+    } else {
+        "ls"
+    };
+    let error = match flag(args, "method", String::from(method)).as_str() {
+        "ls" => {
+            tune::least_squares(&mut eval, samples).unwrap_or_else(|err| fail(&err.to_string()))
+        }
+        "gd" => tune::gradient_descent(
+            &mut eval,
+            samples,
+            flag(args, "epochs", 10),
+            flag(args, "rate", 0.000003),
+        ),
+        _ => fail("Unknown method"),
+    };
+    println!("Final error:   {error:.3}");
+
+    if let Err(err) = eval.save_weights(Path::new(out)) {
+        fail(&format!("Could not write {out}: {err}"));
+    }
+    println!("Weights written to {out}");
+}
+
+fn run_fit(args: &Args) {
+    let [dataset, out] = args.positional()[..] else {
+        fail("Expected a dataset and an output file");
+    };
+
+    let scale: f64 = flag(args, "scale", 1.0);
+    let mut samples = tune::read_dataset(dataset)
+        .unwrap_or_else(|err| fail(&format!("Could not read {dataset}: {err}")));
+    for sample in &mut samples {
+        sample.target *= scale;
+    }
+    println!("Loaded {} samples", samples.len());
+
+    let init = args.get("init");
+    // A config evaluator fits whichever features its --init file lists, GoodEval's by default.
+    match flag(args, "eval", String::from("config")).as_str() {
+        "config" | "good" => {
+            let eval = match init {
+                Some(path) => ConfigEval::from_file(path),
+                None => Ok(GoodEval::default()),
+            };
+            let eval = eval.unwrap_or_else(|err| fail(&format!("Could not load weights: {err}")));
+            fit(eval, &samples, args, out);
+        }
+        "tapered" => {
+            let eval = match init {
+                Some(path) => TaperedEval::from_file(path),
+                None => Ok(TaperedEval::default()),
+            };
+            let eval = eval.unwrap_or_else(|err| fail(&format!("Could not load weights: {err}")));
+            fit(eval, &samples, args, out);
+        }
+        "pattern" => {
+            let eval = match init {
+                Some(path) => PatternEval::from_file(path),
+                None => Ok(PatternEval::default()),
+            };
+            let eval = eval.unwrap_or_else(|err| fail(&format!("Could not load weights: {err}")));
+            fit(eval, &samples, args, out);
+        }
+        _ => fail("Unknown evaluator"),
+    }
+}
+
+// Plays slightly randomised games and labels every position with the final disc differential.
+fn run_generate(args: &Args) {
+    let [out, games] = args.positional()[..] else {
+        fail("Expected an output file and a number of games");
+    };
+    let games: u32 = games
+        .parse()
+        .unwrap_or_else(|_| fail("Invalid number of games"));
+    let depth: u8 = flag(args, "depth", 4);
+    let seed: u64 = flag(args, "seed", 0x_A142_3141_A150_4411);
+
+    let mut x = Mixed::new(
+        Negamax::new('x', depth, GoodEval::default()),
+        'x',
+        seed,
+        0.1,
+    );
+    let mut o = Mixed::new(
+        Negamax::new('o', depth, GoodEval::default()),
+        'o',
+        !seed,
+        0.1,
+    );
+
+    let mut samples = Vec::new();
+    for _ in 0..games {
+        let mut board = Board::new();
+        let mut x_turn = true;
+        let mut positions = Vec::new();
+
+        while !board.is_over() {
+            positions.push(board.clone());
+            let mv = if x_turn {
+                x.get_move(board.clone())
+            } else {
+                o.get_move(board.clone())
+            };
+            if let Some(mv) = mv {
+                board.apply_move(mv, x_turn);
+            }
+            x_turn = !x_turn;
+        }
+
+        let target = board.get_x().count_ones() as f64 - board.get_o().count_ones() as f64;
+        samples.extend(positions.into_iter().map(|board| Sample { board, target }));
+    }
+
+    if let Err(err) = tune::write_dataset(out, &samples) {
+        fail(&format!("Could not write {out}: {err}"));
+    }
+    println!("Wrote {} samples to {out}", samples.len());
+}
+
+// Labels every position of expert games with black's final disc differential,
+// or with the theoretical one computed by perfect endgame play.
+fn run_wthor(args: &Args) {
+    let [out, files @ ..] = &args.positional()[..] else {
+        fail("Expected an output file and WTHOR files");
+    };
+    let theoretical: bool = flag(args, "theoretical", false);
+
+    let mut samples = Vec::new();
+    for &path in files {
+        let database = WthorDatabase::from_file(path)
+            .unwrap_or_else(|err| fail(&format!("Could not read {path}: {err}")));
+        for (idx, reason) in &database.rejected {
+            eprintln!("{path}: skipped game {idx}, {reason}");
+        }
+        for game in &database.games {
+            let score = if theoretical {
+                game.theoretical_score
+            } else {
+                game.black_score
+            };
+            let target = 2.0 * score as f64 - 64.0;
+            let mut positions = game.record.positions();
+            positions.pop(); // the final board
+            samples.extend(
+                positions
+                    .into_iter()
+                    .map(|(board, _)| Sample { board, target }),
+            );
+        }
+        println!("{path}: {} games", database.games.len());
+    }
+
+    if let Err(err) = tune::write_dataset(out, &samples) {
+        fail(&format!("Could not write {out}: {err}"));
+    }
+    println!("Wrote {} samples to {out}", samples.len());
+}
+
+// Converts a text dataset into the binary format used to train NnEval offline.
+fn run_export(args: &Args) {
+    let [dataset, out] = args.positional()[..] else {
+        fail("Expected a dataset and an output file");
+    };
+    let samples = tune::read_dataset(dataset)
+        .unwrap_or_else(|err| fail(&format!("Could not read {dataset}: {err}")));
+    if let Err(err) = nn::export_training_data(out, &samples) {
+        fail(&format!("Could not write {out}: {err}"));
+    }
+    println!("Exported {} samples to {out}", samples.len());
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let Some((command, rest)) = args.split_first() else {
+        fail("Missing command");
+    };
+    let args = Args::parse(rest.iter().cloned()).unwrap_or_else(|err| fail(&err));
+
+    match command.as_str() {
+        "fit" => run_fit(&args),
+        "generate" => run_generate(&args),
+        "export" => run_export(&args),
+        "wthor" => run_wthor(&args),
+        _ => fail("Unknown command"),
+    }
+}
@@ -32,6 +32,19 @@ fn nw(x: u64) -> u64 {
 fn sw(x: u64) -> u64 {
     (x << 7) & NOT_RANK_8 & NOT_FILE_H
 }
+type Ray = fn(u64) -> u64;
+
+// Squares along one axis whose whole line is occupied.
+fn full_lines(occupied: u64, ray: Ray, back: Ray) -> u64 {
+    let mut forward = occupied;
+    let mut backward = occupied;
+    for _ in 0..7 {
+        forward = occupied & (back(forward) | !back(u64::MAX));
+        backward = occupied & (ray(backward) | !ray(u64::MAX));
+    }
+    forward & backward
+}
+
 #[derive(Clone)]
 pub struct Board {
     x: u64,
@@ -46,6 +59,50 @@ impl Board {
             o: (1 << 28) + (1 << 35),
         }
     }
+    pub const fn from_bitboards(x: u64, o: u64) -> Self {
+        Self { x, o }
+    }
+
+    // Parses 64 squares written row by row from a8 to h1, as x, o or - for empty.
+    pub fn from_compact(s: &str) -> Option<Self> {
+        let mut board = Board::from_bitboards(0, 0);
+        let mut idx = 0;
+        for c in s.chars() {
+            if idx == 64 {
+                return None;
+            }
+            match c {
+                'x' | 'X' | '*' => board.x |= 1 << idx,
+                'o' | 'O' => board.o |= 1 << idx,
+                '-' | '.' => {}
+                _ => return None,
+            }
+            idx += 1;
+        }
+        if idx == 64 { Some(board) } else { None }
+    }
+
+    // A compact board followed by the side to move, x or o.
+    pub fn parse_position(s: &str) -> Option<(Self, bool)> {
+        let (board, side) = s.trim().split_once(char::is_whitespace)?;
+        let x_turn = match side.trim() {
+            "x" | "X" | "*" => true,
+            "o" | "O" => false,
+            _ => return None,
+        };
+        Some((Board::from_compact(board)?, x_turn))
+    }
+
+    pub fn to_compact(&self) -> String {
+        (0..8)
+            .flat_map(|row| (0..8).map(move |col| (row, col)))
+            .map(|(row, col)| match self.at(row, col) {
+                '·' => '-',
+                symbol => symbol,
+            })
+            .collect()
+    }
+
     pub const fn get_x(&self) -> u64 {
         self.x
     }
@@ -62,6 +119,24 @@ impl Board {
         1 << Board::get_idx(row, col)
     }
 
+    // Squares are named like a3, with rank 8 on the top row of the board.
+    pub fn parse_square(name: &str) -> Option<u64> {
+        let mut chars = name.chars();
+        let file = chars.next()?.to_ascii_lowercase();
+        let rank = chars.next()?;
+        if chars.next().is_some() || !('a'..='h').contains(&file) || !('1'..='8').contains(&rank) {
+            return None;
+        }
+        Some(Board::get_move(b'8' - rank as u8, file as u8 - b'a'))
+    }
+
+    pub fn square_name(mv: u64) -> String {
+        let idx = mv.trailing_zeros() as u8;
+        let file = (b'a' + idx % 8) as char;
+        let rank = (b'8' - idx / 8) as char;
+        format!("{file}{rank}")
+    }
+
     const fn get_me_opp(&self, x_turn: bool) -> (u64, u64) {
         if x_turn {
             (self.x, self.o)
@@ -152,6 +227,32 @@ impl Board {
         self.legal_moves(true) == 0 && self.legal_moves(false) == 0
     }
 
+    // All squares touching at least one of the given squares.
+    pub fn neighbours(bits: u64) -> u64 {
+        e(bits) | w(bits) | n(bits) | s(bits) | ne(bits) | nw(bits) | se(bits) | sw(bits)
+    }
+
+    // Discs that can never be flipped again. A disc is stable when along every axis its line is full
+    // or one of its neighbours is the edge of the board or another stable disc.
+    pub fn stable_discs(&self, x_turn: bool) -> u64 {
+        let (me, _) = self.get_me_opp(x_turn);
+        let occupied = self.x | self.o;
+        let axes: [(Ray, Ray); 4] = [(e, w), (n, s), (ne, sw), (nw, se)];
+        let full = axes.map(|(ray, back)| full_lines(occupied, ray, back));
+
+        let mut stable = 0;
+        loop {
+            let mut next = me;
+            for ((ray, back), full) in axes.iter().zip(full) {
+                next &= full | back(stable) | !back(u64::MAX) | ray(stable) | !ray(u64::MAX);
+            }
+            if next == stable {
+                return stable;
+            }
+            stable = next;
+        }
+    }
+
     pub fn moves_iter(&self, x_turn: bool) -> MovesIter {
         MovesIter {
             moves: self.legal_moves(x_turn),
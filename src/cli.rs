@@ -0,0 +1,46 @@
+use std::str::FromStr;
+
+// Command line arguments split into positional ones and --flag value pairs.
+pub struct Args {
+    positional: Vec<String>,
+    flags: Vec<(String, String)>,
+}
+
+impl Args {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut positional = Vec::new();
+        let mut flags = Vec::new();
+        let mut iter = args.into_iter();
+        while let Some(arg) = iter.next() {
+            if let Some(flag) = arg.strip_prefix("--") {
+                let Some(value) = iter.next() else {
+                    return Err(format!("Missing value for --{flag}"));
+                };
+                flags.push((flag.to_string(), value));
+            } else {
+                positional.push(arg);
+            }
+        }
+        Ok(Self { positional, flags })
+    }
+
+    pub fn positional(&self) -> Vec<&str> {
+        self.positional.iter().map(String::as_str).collect()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.flags
+            .iter()
+            .find(|(flag, _)| flag == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn value<T: FromStr>(&self, name: &str, default: T) -> Result<T, String> {
+        match self.get(name) {
+            Some(value) => value
+                .parse()
+                .map_err(|_| format!("Invalid value for --{name}: {value}")),
+            None => Ok(default),
+        }
+    }
+}
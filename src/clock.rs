@@ -0,0 +1,162 @@
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+// How much time players get: a base for the whole game plus an increment after every move,
+// or a fixed amount for each move. Written as 300+5 or 2/move, in seconds.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum TimeControl {
+    Increment { base: Duration, increment: Duration },
+    PerMove(Duration),
+}
+
+impl TimeControl {
+    // Time for the next move when remaining is left and the given number of squares are empty.
+    // Keeps a margin so engines don't lose on time through the overhead around their search.
+    pub fn budget(&self, remaining: Duration, empties: u32) -> Duration {
+        match *self {
+            TimeControl::PerMove(_) => remaining.mul_f64(0.9),
+            TimeControl::Increment { increment, .. } => {
+                // About half the empty squares are still ours to fill.
+                let moves_left = (empties / 2).max(1);
+                let budget = remaining / moves_left + increment.mul_f64(0.8);
+                budget.min(remaining.mul_f64(0.5))
+            }
+        }
+    }
+}
+
+impl FromStr for TimeControl {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let seconds = |text: &str| {
+            text.trim()
+                .parse::<f64>()
+                .ok()
+                .and_then(|s| Duration::try_from_secs_f64(s).ok())
+                .ok_or_else(|| format!("invalid time control {s}"))
+        };
+        if let Some(per_move) = s.strip_suffix("/move") {
+            Ok(TimeControl::PerMove(seconds(per_move)?))
+        } else {
+            let (base, increment) = s.split_once('+').unwrap_or((s, "0"));
+            Ok(TimeControl::Increment {
+                base: seconds(base)?,
+                increment: seconds(increment)?,
+            })
+        }
+    }
+}
+
+impl fmt::Display for TimeControl {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TimeControl::Increment { base, increment } => {
+                write!(f, "{}+{}", base.as_secs_f64(), increment.as_secs_f64())
+            }
+            TimeControl::PerMove(time) => write!(f, "{}/move", time.as_secs_f64()),
+        }
+    }
+}
+
+// Both players' remaining time under a time control.
+#[derive(Clone)]
+pub struct Clock {
+    control: TimeControl,
+    x: Duration,
+    o: Duration,
+}
+
+impl Clock {
+    pub const fn new(control: TimeControl) -> Self {
+        let start = match control {
+            TimeControl::Increment { base, .. } => base,
+            TimeControl::PerMove(time) => time,
+        };
+        Self {
+            control,
+            x: start,
+            o: start,
+        }
+    }
+
+    // A clock part way through a game, for example one loaded from a save.
+    pub const fn with_remaining(control: TimeControl, x: Duration, o: Duration) -> Self {
+        Self { control, x, o }
+    }
+
+    pub const fn control(&self) -> TimeControl {
+        self.control
+    }
+
+    pub const fn remaining(&self, x_turn: bool) -> Duration {
+        if x_turn { self.x } else { self.o }
+    }
+
+    // Charges a move to the side that made it, false if that side ran out of time.
+    pub fn spend(&mut self, x_turn: bool, elapsed: Duration) -> bool {
+        if !self.charge(x_turn, elapsed) {
+            return false;
+        }
+        let remaining = if x_turn { &mut self.x } else { &mut self.o };
+        *remaining = match self.control {
+            TimeControl::Increment { increment, .. } => remaining.saturating_add(increment),
+            TimeControl::PerMove(time) => time,
+        };
+        true
+    }
+
+    // Takes time off a side without a move being made, like spend without the increment.
+    pub fn charge(&mut self, x_turn: bool, elapsed: Duration) -> bool {
+        let remaining = if x_turn { &mut self.x } else { &mut self.o };
+        match remaining.checked_sub(elapsed) {
+            Some(left) => {
+                *remaining = left;
+                true
+            }
+            None => {
+                *remaining = Duration::ZERO;
+                false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_writes_time_controls() {
+        for text in ["300+5", "0.5+0.01", "10/move"] {
+            let control: TimeControl = text.parse().unwrap();
+            assert_eq!(control.to_string(), text);
+        }
+        assert_eq!(
+            "60".parse(),
+            Ok(TimeControl::Increment {
+                base: Duration::from_secs(60),
+                increment: Duration::ZERO,
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_times_out_of_range() {
+        for text in ["1e30", "-1+0", "5+inf", "NaN/move", "fast"] {
+            assert!(text.parse::<TimeControl>().is_err(), "{text}");
+        }
+    }
+
+    #[test]
+    fn increment_is_added_after_the_move() {
+        let mut clock = Clock::new("10+2".parse().unwrap());
+        assert!(clock.spend(true, Duration::from_secs(3)));
+        assert_eq!(clock.remaining(true), Duration::from_secs(9));
+        assert!(clock.charge(false, Duration::from_secs(4)));
+        assert_eq!(clock.remaining(false), Duration::from_secs(6));
+        assert!(!clock.spend(true, Duration::from_secs(10)));
+        assert_eq!(clock.remaining(true), Duration::ZERO);
+    }
+}
@@ -0,0 +1,119 @@
+use crate::board::Board;
+use crate::eval::{Evaluator, MAX_SCORE, Term};
+
+impl<E: Evaluator + ?Sized> Evaluator for Box<E> {
+    fn eval(&self, board: &Board) -> i32 {
+        (**self).eval(board)
+    }
+
+    fn explain(&self, board: &Board) -> Option<Vec<Term>> {
+        (**self).explain(board)
+    }
+}
+
+// Adds up several evaluators, each scaled by its own weight. The sum is kept within the scores
+// of finished games.
+#[derive(Default)]
+pub struct WeightedSum {
+    parts: Vec<(Box<dyn Evaluator>, i32)>,
+}
+
+impl WeightedSum {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(mut self, eval: impl Evaluator + 'static, weight: i32) -> Self {
+        self.parts.push((Box::new(eval), weight));
+        self
+    }
+}
+
+impl Evaluator for WeightedSum {
+    fn eval(&self, board: &Board) -> i32 {
+        let sum: i64 = self
+            .parts
+            .iter()
+            .map(|(eval, weight)| eval.eval(board) as i64 * *weight as i64)
+            .sum();
+        sum.clamp(-MAX_SCORE as i64, MAX_SCORE as i64) as i32
+    }
+
+    // Terms of every part are prefixed with the part's position in the sum.
+    fn explain(&self, board: &Board) -> Option<Vec<Term>> {
+        let mut terms = Vec::new();
+        for (i, (eval, weight)) in self.parts.iter().enumerate() {
+            for term in eval.explain(board)? {
+                terms.push(Term::new(
+                    format!("{i}.{}", term.name),
+                    term.value,
+                    term.weight.saturating_mul(*weight),
+                ));
+            }
+        }
+        Some(terms)
+    }
+}
+
+// Hands the board to a different evaluator depending on how many squares are still empty.
+// It starts with the evaluator for the endgame, so every position has a phase.
+pub struct PhaseSwitch {
+    phases: Vec<(u32, Box<dyn Evaluator>)>,
+}
+
+impl PhaseSwitch {
+    pub fn new(endgame: impl Evaluator + 'static) -> Self {
+        Self {
+            phases: vec![(0, Box::new(endgame))],
+        }
+    }
+
+    // Uses eval while at least min_empties squares are empty and no later-starting phase applies.
+    // A phase starting at the same number replaces the earlier one.
+    pub fn phase(mut self, min_empties: u32, eval: impl Evaluator + 'static) -> Self {
+        self.phases.retain(|&(min, _)| min != min_empties);
+        self.phases.push((min_empties, Box::new(eval)));
+        self.phases.sort_by_key(|&(min, _)| std::cmp::Reverse(min));
+        self
+    }
+
+    fn select(&self, board: &Board) -> &dyn Evaluator {
+        let empties = (!(board.get_x() | board.get_o())).count_ones();
+        // The endgame phase starts at 0 empty squares, so one always matches.
+        self.phases
+            .iter()
+            .find(|&&(min, _)| empties >= min)
+            .map(|(_, eval)| eval.as_ref())
+            .unwrap()
+    }
+}
+
+impl Evaluator for PhaseSwitch {
+    fn eval(&self, board: &Board) -> i32 {
+        self.select(board).eval(board)
+    }
+
+    fn explain(&self, board: &Board) -> Option<Vec<Term>> {
+        self.select(board).explain(board)
+    }
+}
+
+// Turns x's perspective into the perspective of the side to move, as negamax needs it.
+pub struct SideRelative<E: Evaluator> {
+    eval: E,
+}
+
+impl<E: Evaluator> SideRelative<E> {
+    pub const fn new(eval: E) -> Self {
+        Self { eval }
+    }
+
+    pub fn eval_for(&self, board: &Board, x_turn: bool) -> i32 {
+        let score = self.eval.eval(board);
+        if x_turn { score } else { -score }
+    }
+
+    pub const fn inner(&self) -> &E {
+        &self.eval
+    }
+}
@@ -0,0 +1,113 @@
+use crate::board::Board;
+use crate::eval::features::{Context, Feature};
+use crate::eval::tune::{self, Tunable};
+use crate::eval::{Evaluator, Term};
+use crate::utils::invalid_data;
+use std::io;
+use std::path::Path;
+
+const GOOD_PRESET: &str = include_str!("../../presets/good.txt");
+const STRATEGIC_PRESET: &str = include_str!("../../presets/strategic.txt");
+
+// A weighted sum of features read from a config file with one "feature weight" pair per line.
+#[derive(Clone)]
+pub struct ConfigEval {
+    terms: Vec<(Feature, i32)>,
+}
+
+impl ConfigEval {
+    pub const fn new(terms: Vec<(Feature, i32)>) -> Self {
+        Self { terms }
+    }
+
+    pub fn parse(text: &str) -> io::Result<Self> {
+        let terms = tune::parse_weights(text)?
+            .into_iter()
+            .map(|(name, weight)| match Feature::from_name(&name) {
+                Some(feature) => Ok((feature, weight)),
+                None => Err(invalid_data(format!("unknown feature: {name}"))),
+            })
+            .collect::<io::Result<_>>()?;
+        Ok(Self::new(terms))
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    // The weights GoodEval was originally written with.
+    pub fn good() -> Self {
+        Self::parse(GOOD_PRESET).expect("shipped preset is valid")
+    }
+
+    // Penalises X-squares and C-squares only next to empty corners and adds region parity.
+    pub fn strategic() -> Self {
+        Self::parse(STRATEGIC_PRESET).expect("shipped preset is valid")
+    }
+
+    pub fn terms(&self) -> &[(Feature, i32)] {
+        &self.terms
+    }
+}
+
+impl Default for ConfigEval {
+    fn default() -> Self {
+        Self::good()
+    }
+}
+
+impl Evaluator for ConfigEval {
+    fn eval(&self, board: &Board) -> i32 {
+        let ctx = Context::new(board);
+        if ctx.is_over() {
+            return ctx.final_score();
+        }
+        self.terms
+            .iter()
+            .map(|&(feature, weight)| feature.value(&ctx) * weight)
+            .sum()
+    }
+
+    fn explain(&self, board: &Board) -> Option<Vec<Term>> {
+        let ctx = Context::new(board);
+        if ctx.is_over() {
+            return Some(Vec::new());
+        }
+        let terms = self
+            .terms
+            .iter()
+            .map(|&(feature, weight)| Term::new(feature.name(), feature.value(&ctx) as f64, weight))
+            .collect();
+        Some(terms)
+    }
+}
+
+impl Tunable for ConfigEval {
+    fn num_weights(&self) -> usize {
+        self.terms.len()
+    }
+
+    fn features(&self, board: &Board) -> Vec<(usize, f64)> {
+        let ctx = Context::new(board);
+        self.terms
+            .iter()
+            .enumerate()
+            .map(|(i, &(feature, _))| (i, feature.value(&ctx) as f64))
+            .collect()
+    }
+
+    fn weights(&self) -> Vec<i32> {
+        self.terms.iter().map(|&(_, weight)| weight).collect()
+    }
+
+    fn set_weights(&mut self, weights: &[i32]) {
+        for ((_, weight), &new) in self.terms.iter_mut().zip(weights) {
+            *weight = new;
+        }
+    }
+
+    fn save_weights(&self, path: &Path) -> io::Result<()> {
+        let names: Vec<&str> = self.terms.iter().map(|(f, _)| f.name()).collect();
+        tune::write_weights(path, &names, &self.weights())
+    }
+}
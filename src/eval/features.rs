@@ -0,0 +1,278 @@
+use crate::board::Board;
+
+const CORNERS: u64 = 0x8100000000000081;
+const EDGES: u64 = 0x7e8181818181817e;
+const RING: u64 = 0x7e424242427e00; // The outer edge of the board but 1 tile smaller
+const X_SQUARES: u64 = 0x0042000000004200; // Diagonally next to a corner
+const C_SQUARES: u64 = 0x4281000000008142; // Next to a corner along an edge
+
+// Each corner with its X-square and C-squares.
+const CORNER_ZONES: [(u64, u64, u64); 4] = [
+    (1 << 0, 1 << 9, (1 << 1) | (1 << 8)),
+    (1 << 7, 1 << 14, (1 << 6) | (1 << 15)),
+    (1 << 56, 1 << 49, (1 << 48) | (1 << 57)),
+    (1 << 63, 1 << 54, (1 << 55) | (1 << 62)),
+];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Feature {
+    Discs,
+    Mobility,
+    PotentialMobility,
+    Corners,
+    XSquares,
+    CSquares,
+    XSquaresOpen,
+    CSquaresOpen,
+    Edges,
+    Ring,
+    Frontier,
+    Stability,
+    RegionParity,
+}
+
+impl Feature {
+    pub const ALL: [Feature; 13] = [
+        Feature::Discs,
+        Feature::Mobility,
+        Feature::PotentialMobility,
+        Feature::Corners,
+        Feature::XSquares,
+        Feature::CSquares,
+        Feature::XSquaresOpen,
+        Feature::CSquaresOpen,
+        Feature::Edges,
+        Feature::Ring,
+        Feature::Frontier,
+        Feature::Stability,
+        Feature::RegionParity,
+    ];
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Feature::Discs => "discs",
+            Feature::Mobility => "mobility",
+            Feature::PotentialMobility => "potential_mobility",
+            Feature::Corners => "corners",
+            Feature::XSquares => "x_squares",
+            Feature::CSquares => "c_squares",
+            Feature::XSquaresOpen => "x_squares_open",
+            Feature::CSquaresOpen => "c_squares_open",
+            Feature::Edges => "edges",
+            Feature::Ring => "ring",
+            Feature::Frontier => "frontier",
+            Feature::Stability => "stability",
+            Feature::RegionParity => "region_parity",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Feature> {
+        Feature::ALL.into_iter().find(|f| f.name() == name)
+    }
+
+    // Value of the feature for x minus its value for o.
+    pub fn value(self, ctx: &Context) -> i32 {
+        let (x, o) = (ctx.x, ctx.o);
+        match self {
+            Feature::Discs => diff(x, o),
+            Feature::Mobility => diff(ctx.moves_x, ctx.moves_o),
+            Feature::PotentialMobility => diff(
+                ctx.empty & Board::neighbours(o),
+                ctx.empty & Board::neighbours(x),
+            ),
+            Feature::Corners => diff(x & CORNERS, o & CORNERS),
+            Feature::XSquares => diff(x & X_SQUARES, o & X_SQUARES),
+            Feature::CSquares => diff(x & C_SQUARES, o & C_SQUARES),
+            // Only squares next to an empty corner can give that corner away.
+            Feature::XSquaresOpen => {
+                let (open_x, _) = open_zones(ctx.empty);
+                diff(x & open_x, o & open_x)
+            }
+            Feature::CSquaresOpen => {
+                let (_, open_c) = open_zones(ctx.empty);
+                diff(x & open_c, o & open_c)
+            }
+            Feature::Edges => diff(x & EDGES, o & EDGES),
+            Feature::Ring => diff(x & RING, o & RING),
+            Feature::Frontier => {
+                let frontier = Board::neighbours(ctx.empty);
+                diff(x & frontier, o & frontier)
+            }
+            Feature::Stability => diff(ctx.board.stable_discs(true), ctx.board.stable_discs(false)),
+            // Odd regions that only one side can move into, that side gets the last disc there.
+            // This is the only parity term: a whole-board one needs the side to move, which
+            // evaluators don't get, and without it only flips sign every ply.
+            Feature::RegionParity => empty_regions(ctx.empty)
+                .into_iter()
+                .filter(|region| region.count_ones() % 2 == 1)
+                .map(
+                    |region| match (region & ctx.moves_x != 0, region & ctx.moves_o != 0) {
+                        (true, false) => 1,
+                        (false, true) => -1,
+                        _ => 0,
+                    },
+                )
+                .sum(),
+        }
+    }
+}
+
+// X-squares and C-squares whose corner is still empty.
+fn open_zones(empty: u64) -> (u64, u64) {
+    CORNER_ZONES
+        .iter()
+        .filter(|(corner, _, _)| corner & empty != 0)
+        .fold((0, 0), |(xs, cs), (_, x_sq, c_sq)| (xs | x_sq, cs | c_sq))
+}
+
+// Connected groups of empty squares, touching squares including diagonals count as connected.
+fn empty_regions(mut empty: u64) -> Vec<u64> {
+    let mut regions = Vec::new();
+    while empty != 0 {
+        let mut region = empty & empty.wrapping_neg();
+        loop {
+            let grown = (region | Board::neighbours(region)) & empty;
+            if grown == region {
+                break;
+            }
+            region = grown;
+        }
+        empty &= !region;
+        regions.push(region);
+    }
+    regions
+}
+
+fn diff(x: u64, o: u64) -> i32 {
+    x.count_ones() as i32 - o.count_ones() as i32
+}
+
+// Bitboards shared by every feature of a position, computed once per evaluation.
+pub struct Context<'a> {
+    board: &'a Board,
+    x: u64,
+    o: u64,
+    empty: u64,
+    moves_x: u64,
+    moves_o: u64,
+}
+
+impl<'a> Context<'a> {
+    pub fn new(board: &'a Board) -> Self {
+        let x = board.get_x();
+        let o = board.get_o();
+        Self {
+            board,
+            x,
+            o,
+            empty: !(x | o),
+            moves_x: board.legal_moves(true),
+            moves_o: board.legal_moves(false),
+        }
+    }
+
+    pub const fn is_over(&self) -> bool {
+        self.moves_x == 0 && self.moves_o == 0
+    }
+
+    pub const fn final_score(&self) -> i32 {
+        super::final_score(self.x.count_ones(), self.o.count_ones())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eval::{ConfigEval, Evaluator};
+
+    fn value(feature: Feature, board: &Board) -> i32 {
+        feature.value(&Context::new(board))
+    }
+
+    // x has a disc on b7, once with the a8 corner empty and once owning it.
+    const X_SQUARE_OPEN: &str = concat!(
+        "--------", "-x------", "--o-----", "---xo---", "---ox---", "--------", "--------",
+        "--------",
+    );
+    const X_SQUARE_SAFE: &str = concat!(
+        "x-------", "-x------", "--o-----", "---xo---", "---ox---", "--------", "--------",
+        "--------",
+    );
+
+    #[test]
+    fn x_square_penalty_depends_on_corner() {
+        let open = Board::from_compact(X_SQUARE_OPEN).unwrap();
+        let safe = Board::from_compact(X_SQUARE_SAFE).unwrap();
+
+        // The flat ring term can't tell the two apart.
+        assert_eq!(value(Feature::Ring, &open), value(Feature::Ring, &safe));
+        assert_eq!(value(Feature::XSquaresOpen, &open), 1);
+        assert_eq!(value(Feature::XSquaresOpen, &safe), 0);
+    }
+
+    #[test]
+    fn c_square_penalty_depends_on_corner() {
+        let open = Board::from_compact(concat!(
+            "-o------", "--------", "--x-----", "---xo---", "---ox---", "--------", "--------",
+            "--------",
+        ))
+        .unwrap();
+        let safe = Board::from_compact(concat!(
+            "oo------", "--------", "--x-----", "---xo---", "---ox---", "--------", "--------",
+            "--------",
+        ))
+        .unwrap();
+
+        assert_eq!(
+            value(Feature::CSquares, &open),
+            value(Feature::CSquares, &safe)
+        );
+        assert_eq!(value(Feature::CSquaresOpen, &open), -1);
+        assert_eq!(value(Feature::CSquaresOpen, &safe), 0);
+    }
+
+    #[test]
+    fn strategic_preset_prefers_safe_x_square() {
+        let open = Board::from_compact(X_SQUARE_OPEN).unwrap();
+        let safe = Board::from_compact(X_SQUARE_SAFE).unwrap();
+        let good = ConfigEval::good();
+        let strategic = ConfigEval::strategic();
+
+        // Owning the corner is worth more when the X-square next to it stops being a liability.
+        let good_gain = good.eval(&safe) - good.eval(&open);
+        let strategic_gain = strategic.eval(&safe) - strategic.eval(&open);
+        assert!(strategic_gain > good_gain);
+    }
+
+    #[test]
+    fn odd_region_reachable_by_one_side() {
+        // h1 is the only odd region and only x can play there.
+        let board = Board::from_compact(concat!(
+            "--oooooo", "oooooooo", "oooooooo", "oooooooo", "oooooooo", "oooooooo", "oooooooo",
+            "xoooooo-",
+        ))
+        .unwrap();
+        assert_eq!(value(Feature::RegionParity, &board), 1);
+
+        // Same position with the h1 region made even.
+        let board = Board::from_compact(concat!(
+            "--oooooo", "oooooooo", "oooooooo", "oooooooo", "oooooooo", "oooooooo", "oooooooo",
+            "xooooo--",
+        ))
+        .unwrap();
+        assert_eq!(value(Feature::RegionParity, &board), 0);
+    }
+
+    #[test]
+    fn regions_are_split_by_discs() {
+        let board = Board::from_compact(concat!(
+            "--oooooo", "oooooooo", "oooooooo", "ooo-oooo", "oooooooo", "oooooooo", "oooooooo",
+            "xoooooo-",
+        ))
+        .unwrap();
+        let regions = empty_regions(!(board.get_x() | board.get_o()));
+        let mut sizes: Vec<u32> = regions.iter().map(|r| r.count_ones()).collect();
+        sizes.sort();
+        assert_eq!(sizes, vec![1, 1, 2]);
+    }
+}
@@ -0,0 +1,92 @@
+pub mod combinators;
+pub mod config;
+pub mod features;
+pub mod nn;
+pub mod pattern;
+pub mod spec;
+pub mod tapered;
+pub mod td;
+pub mod tune;
+
+use crate::board::Board;
+
+pub use combinators::{PhaseSwitch, SideRelative, WeightedSum};
+pub use config::ConfigEval;
+pub use features::Feature;
+pub use nn::NnEval;
+pub use pattern::PatternEval;
+pub use spec::EvalSpec;
+pub use tapered::TaperedEval;
+pub use td::TdTrainer;
+pub use tune::Tunable;
+
+// The original hand-tuned evaluator lives on as a preset of ConfigEval, which is also its default,
+// so it is built with GoodEval::default() or ConfigEval::good().
+pub type GoodEval = ConfigEval;
+
+// Score of a won game. Evaluations stay within plus or minus this, so negating them is safe.
+pub(crate) const MAX_SCORE: i32 = i32::MAX - 8;
+
+// Score of a finished game, large enough to beat any heuristic evaluation.
+pub(crate) const fn final_score(score_x: u32, score_o: u32) -> i32 {
+    if score_x > score_o {
+        MAX_SCORE
+    } else if score_x < score_o {
+        -MAX_SCORE
+    } else {
+        0
+    }
+}
+
+// Keeps a heuristic score strictly between the scores of a lost and a won game.
+pub(crate) fn clamp_heuristic(score: i64) -> i32 {
+    score.clamp(-(MAX_SCORE as i64 - 1), MAX_SCORE as i64 - 1) as i32
+}
+
+// One feature's share of an evaluation.
+pub struct Term {
+    pub name: String,
+    pub value: f64,
+    pub weight: i32,
+    pub contribution: f64,
+}
+
+impl Term {
+    pub fn new(name: impl Into<String>, value: f64, weight: i32) -> Self {
+        Self {
+            name: name.into(),
+            value,
+            weight,
+            contribution: value * weight as f64 + 0.0, // avoids printing -0
+        }
+    }
+}
+
+pub trait Evaluator {
+    fn eval(&self, board: &Board) -> i32;
+
+    // Per-feature breakdown of eval(), None if the evaluator can't be split into features.
+    // Finished games are scored by the result alone, so they have no terms.
+    fn explain(&self, _board: &Board) -> Option<Vec<Term>> {
+        None
+    }
+}
+
+pub struct SimpleEval {}
+
+impl Evaluator for SimpleEval {
+    fn eval(&self, board: &Board) -> i32 {
+        let score_x = board.get_x().count_ones();
+        let score_o = board.get_o().count_ones();
+        score_x as i32 - score_o as i32
+    }
+
+    fn explain(&self, board: &Board) -> Option<Vec<Term>> {
+        let ctx = features::Context::new(board);
+        Some(vec![Term::new(
+            "discs",
+            Feature::Discs.value(&ctx) as f64,
+            1,
+        )])
+    }
+}
@@ -0,0 +1,204 @@
+use crate::board::Board;
+use crate::eval::tune::Sample;
+use crate::eval::{Evaluator, clamp_heuristic, final_score};
+use crate::utils::invalid_data;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+const MAGIC: &[u8; 4] = b"ONNW";
+const TRAINING_MAGIC: &[u8; 4] = b"ONND";
+
+// Input planes seen from one side: own discs, opponent discs and own legal moves.
+pub const INPUTS: usize = 3 * 64;
+
+// Hidden activations are clipped to [0, ACTIVATION_MAX], which stands for [0, 1] in the float model.
+pub const ACTIVATION_MAX: i32 = 256;
+
+// Keeps the hidden layer on the stack during evaluation.
+pub const MAX_HIDDEN: usize = 512;
+
+// A network with one hidden layer over the input planes. The same network scores the board for
+// both sides and the evaluation is the difference, so it is always from x's perspective.
+//
+// File layout, all little endian: magic, hidden size (u32), output divisor (i32),
+// input weights (INPUTS x hidden i16, input major), hidden biases (hidden i16),
+// output weights (hidden i16), output bias (i32).
+#[derive(Clone)]
+pub struct NnEval {
+    hidden: usize,
+    output_divisor: i32,
+    input_weights: Vec<i16>,
+    hidden_biases: Vec<i16>,
+    output_weights: Vec<i16>,
+    output_bias: i32,
+}
+
+impl NnEval {
+    // A network with every weight zero, the _mut accessors fill it in.
+    pub fn new(hidden: usize, output_divisor: i32) -> Self {
+        assert!(hidden <= MAX_HIDDEN, "at most {MAX_HIDDEN} hidden neurons");
+        Self {
+            hidden,
+            output_divisor,
+            input_weights: vec![0; INPUTS * hidden],
+            hidden_biases: vec![0; hidden],
+            output_weights: vec![0; hidden],
+            output_bias: 0,
+        }
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut reader = BufReader::new(File::open(path)?);
+
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("not a network weight file"));
+        }
+
+        let hidden = read_u32(&mut reader)? as usize;
+        let output_divisor = read_i32(&mut reader)?;
+        if hidden == 0 || hidden > MAX_HIDDEN || output_divisor == 0 {
+            return Err(invalid_data("invalid network header"));
+        }
+
+        let mut eval = NnEval::new(hidden, output_divisor);
+        read_i16s(&mut reader, &mut eval.input_weights)?;
+        read_i16s(&mut reader, &mut eval.hidden_biases)?;
+        read_i16s(&mut reader, &mut eval.output_weights)?;
+        eval.output_bias = read_i32(&mut reader)?;
+        Ok(eval)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(MAGIC)?;
+        writer.write_all(&(self.hidden as u32).to_le_bytes())?;
+        writer.write_all(&self.output_divisor.to_le_bytes())?;
+        for weight in self
+            .input_weights
+            .iter()
+            .chain(&self.hidden_biases)
+            .chain(&self.output_weights)
+        {
+            writer.write_all(&weight.to_le_bytes())?;
+        }
+        writer.write_all(&self.output_bias.to_le_bytes())?;
+        writer.flush()
+    }
+
+    pub const fn hidden(&self) -> usize {
+        self.hidden
+    }
+
+    // Input weights of the given input, one per hidden neuron.
+    pub fn input_weights_mut(&mut self, input: usize) -> &mut [i16] {
+        &mut self.input_weights[input * self.hidden..(input + 1) * self.hidden]
+    }
+
+    pub fn hidden_biases_mut(&mut self) -> &mut [i16] {
+        &mut self.hidden_biases
+    }
+
+    pub fn output_weights_mut(&mut self) -> &mut [i16] {
+        &mut self.output_weights
+    }
+
+    pub const fn set_output_bias(&mut self, bias: i32) {
+        self.output_bias = bias;
+    }
+
+    // Only set inputs contribute, so the hidden layer is built by adding their weight rows.
+    fn forward(&self, own: u64, opp: u64, moves: u64, acc: &mut [i32]) -> i64 {
+        for (a, &b) in acc.iter_mut().zip(&self.hidden_biases) {
+            *a = b as i32;
+        }
+        for (plane, bits) in [own, opp, moves].into_iter().enumerate() {
+            let mut bits = bits;
+            while bits != 0 {
+                let input = plane * 64 + bits.trailing_zeros() as usize;
+                let row = &self.input_weights[input * self.hidden..(input + 1) * self.hidden];
+                for (a, &w) in acc.iter_mut().zip(row) {
+                    *a += w as i32;
+                }
+                bits &= bits - 1;
+            }
+        }
+
+        let out: i64 = acc
+            .iter()
+            .zip(&self.output_weights)
+            .map(|(&a, &w)| a.clamp(0, ACTIVATION_MAX) as i64 * w as i64)
+            .sum();
+        out.saturating_add(self.output_bias as i64) / self.output_divisor as i64
+    }
+}
+
+impl Evaluator for NnEval {
+    fn eval(&self, board: &Board) -> i32 {
+        let x = board.get_x();
+        let o = board.get_o();
+        let moves_x = board.legal_moves(true);
+        let moves_o = board.legal_moves(false);
+
+        // Game over
+        if moves_x == 0 && moves_o == 0 {
+            return final_score(x.count_ones(), o.count_ones());
+        }
+
+        let mut acc = [0; MAX_HIDDEN];
+        let acc = &mut acc[..self.hidden];
+        // Kept below the score of a finished game however large the outputs get.
+        let score = self
+            .forward(x, o, moves_x, acc)
+            .saturating_sub(self.forward(o, x, moves_o, acc));
+        clamp_heuristic(score)
+    }
+}
+
+// Writes positions for offline training. Each record is x, o, x's legal moves and o's legal moves
+// (u64 each) followed by the target for x (i16), all little endian, after a magic and a count.
+pub fn export_training_data(path: impl AsRef<Path>, samples: &[Sample]) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    writer.write_all(TRAINING_MAGIC)?;
+    writer.write_all(&(samples.len() as u32).to_le_bytes())?;
+    for sample in samples {
+        let board = &sample.board;
+        for bits in [
+            board.get_x(),
+            board.get_o(),
+            board.legal_moves(true),
+            board.legal_moves(false),
+        ] {
+            writer.write_all(&bits.to_le_bytes())?;
+        }
+        let target = sample
+            .target
+            .round()
+            .clamp(i16::MIN as f64, i16::MAX as f64) as i16;
+        writer.write_all(&target.to_le_bytes())?;
+    }
+    writer.flush()
+}
+
+fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn read_i32(reader: &mut impl Read) -> io::Result<i32> {
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf)?;
+    Ok(i32::from_le_bytes(buf))
+}
+
+fn read_i16s(reader: &mut impl Read, out: &mut [i16]) -> io::Result<()> {
+    let mut bytes = vec![0u8; out.len() * 2];
+    reader.read_exact(&mut bytes)?;
+    for (value, chunk) in out.iter_mut().zip(bytes.chunks_exact(2)) {
+        *value = i16::from_le_bytes([chunk[0], chunk[1]]);
+    }
+    Ok(())
+}
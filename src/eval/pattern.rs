@@ -0,0 +1,275 @@
+use crate::board::Board;
+use crate::eval::{Evaluator, final_score};
+use crate::utils::invalid_data;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+const MAGIC: &[u8; 4] = b"OPAT";
+pub const DEFAULT_PHASES: usize = 12;
+// One phase per move at most, files claiming more are rejected before allocating.
+pub const MAX_PHASES: usize = 60;
+
+// Patterns are written as (row, col) pairs anchored at the a8 corner.
+// Every other instance is generated from the 8 symmetries of the board.
+const EDGE_2X: &[(u8, u8)] = &[
+    (1, 1),
+    (0, 0),
+    (0, 1),
+    (0, 2),
+    (0, 3),
+    (0, 4),
+    (0, 5),
+    (0, 6),
+    (0, 7),
+    (1, 6),
+];
+const CORNER_3X3: &[(u8, u8)] = &[
+    (0, 0),
+    (0, 1),
+    (0, 2),
+    (1, 0),
+    (1, 1),
+    (1, 2),
+    (2, 0),
+    (2, 1),
+    (2, 2),
+];
+const CORNER_2X5: &[(u8, u8)] = &[
+    (0, 0),
+    (0, 1),
+    (0, 2),
+    (0, 3),
+    (0, 4),
+    (1, 0),
+    (1, 1),
+    (1, 2),
+    (1, 3),
+    (1, 4),
+];
+const LINE_2: &[(u8, u8)] = &[
+    (1, 0),
+    (1, 1),
+    (1, 2),
+    (1, 3),
+    (1, 4),
+    (1, 5),
+    (1, 6),
+    (1, 7),
+];
+const LINE_3: &[(u8, u8)] = &[
+    (2, 0),
+    (2, 1),
+    (2, 2),
+    (2, 3),
+    (2, 4),
+    (2, 5),
+    (2, 6),
+    (2, 7),
+];
+const LINE_4: &[(u8, u8)] = &[
+    (3, 0),
+    (3, 1),
+    (3, 2),
+    (3, 3),
+    (3, 4),
+    (3, 5),
+    (3, 6),
+    (3, 7),
+];
+const DIAG_8: &[(u8, u8)] = &[
+    (0, 0),
+    (1, 1),
+    (2, 2),
+    (3, 3),
+    (4, 4),
+    (5, 5),
+    (6, 6),
+    (7, 7),
+];
+const DIAG_7: &[(u8, u8)] = &[(0, 1), (1, 2), (2, 3), (3, 4), (4, 5), (5, 6), (6, 7)];
+const DIAG_6: &[(u8, u8)] = &[(0, 2), (1, 3), (2, 4), (3, 5), (4, 6), (5, 7)];
+const DIAG_5: &[(u8, u8)] = &[(0, 3), (1, 4), (2, 5), (3, 6), (4, 7)];
+const DIAG_4: &[(u8, u8)] = &[(0, 4), (1, 5), (2, 6), (3, 7)];
+
+pub const PATTERNS: [&[(u8, u8)]; 11] = [
+    EDGE_2X, CORNER_3X3, CORNER_2X5, LINE_2, LINE_3, LINE_4, DIAG_8, DIAG_7, DIAG_6, DIAG_5, DIAG_4,
+];
+
+fn symmetry(sym: u8, (row, col): (u8, u8)) -> (u8, u8) {
+    let (row, col) = if sym & 4 != 0 { (col, row) } else { (row, col) };
+    let row = if sym & 2 != 0 { 7 - row } else { row };
+    let col = if sym & 1 != 0 { 7 - col } else { col };
+    (row, col)
+}
+
+#[derive(Clone)]
+struct Instance {
+    pattern: usize,
+    squares: Vec<u8>,
+}
+
+fn instances() -> Vec<Instance> {
+    let mut instances: Vec<Instance> = Vec::new();
+    for (pattern, shape) in PATTERNS.iter().enumerate() {
+        let mut seen: Vec<Vec<u8>> = Vec::new();
+        for sym in 0..8 {
+            let squares: Vec<u8> = shape
+                .iter()
+                .map(|&sq| {
+                    let (row, col) = symmetry(sym, sq);
+                    row * 8 + col
+                })
+                .collect();
+
+            // Symmetric shapes map onto themselves, only keep one copy.
+            let mut sorted = squares.clone();
+            sorted.sort_unstable();
+            if seen.contains(&sorted) {
+                continue;
+            }
+            seen.push(sorted);
+            instances.push(Instance { pattern, squares });
+        }
+    }
+    instances
+}
+
+// Evaluates the board as a sum of weights looked up by the base 3 index of every pattern instance.
+// A square counts as 0 if empty, 1 for x and 2 for o, so scores are from x's perspective.
+#[derive(Clone)]
+pub struct PatternEval {
+    phases: usize,
+    instances: Vec<Instance>,
+    offsets: Vec<usize>,
+    phase_size: usize,
+    weights: Vec<i16>,
+}
+
+impl PatternEval {
+    pub fn new(phases: usize) -> Self {
+        assert!(
+            (1..=MAX_PHASES).contains(&phases),
+            "pattern evaluators need 1 to {MAX_PHASES} phases"
+        );
+        let mut offsets = Vec::with_capacity(PATTERNS.len());
+        let mut phase_size = 0;
+        for shape in PATTERNS {
+            offsets.push(phase_size);
+            phase_size += 3usize.pow(shape.len() as u32);
+        }
+
+        Self {
+            phases,
+            instances: instances(),
+            offsets,
+            phase_size,
+            weights: vec![0; phases * phase_size],
+        }
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut reader = BufReader::new(File::open(path)?);
+
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("not a pattern weight file"));
+        }
+
+        let mut buf = [0u8; 4];
+        reader.read_exact(&mut buf)?;
+        let phases = u32::from_le_bytes(buf) as usize;
+        reader.read_exact(&mut buf)?;
+        let phase_size = u32::from_le_bytes(buf) as usize;
+
+        if !(1..=MAX_PHASES).contains(&phases) {
+            return Err(invalid_data("invalid number of pattern phases"));
+        }
+        let mut eval = PatternEval::new(phases);
+        if phase_size != eval.phase_size {
+            return Err(invalid_data("pattern table size mismatch"));
+        }
+
+        let mut bytes = vec![0u8; eval.weights.len() * 2];
+        reader.read_exact(&mut bytes)?;
+        for (weight, chunk) in eval.weights.iter_mut().zip(bytes.chunks_exact(2)) {
+            *weight = i16::from_le_bytes([chunk[0], chunk[1]]);
+        }
+        Ok(eval)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(MAGIC)?;
+        writer.write_all(&(self.phases as u32).to_le_bytes())?;
+        writer.write_all(&(self.phase_size as u32).to_le_bytes())?;
+        for weight in &self.weights {
+            writer.write_all(&weight.to_le_bytes())?;
+        }
+        writer.flush()
+    }
+
+    pub const fn num_phases(&self) -> usize {
+        self.phases
+    }
+
+    pub fn weights(&self) -> &[i16] {
+        &self.weights
+    }
+
+    pub fn weights_mut(&mut self) -> &mut [i16] {
+        &mut self.weights
+    }
+
+    // Phases split the 60 moves of the game evenly.
+    pub fn phase(&self, board: &Board) -> usize {
+        let discs = (board.get_x() | board.get_o()).count_ones() as usize;
+        (discs.saturating_sub(4) * self.phases / 61).min(self.phases - 1)
+    }
+
+    fn index(&self, instance: &Instance, x: u64, o: u64) -> usize {
+        let mut idx = 0;
+        for &sq in &instance.squares {
+            let bit = 1u64 << sq;
+            idx *= 3;
+            if x & bit != 0 {
+                idx += 1;
+            } else if o & bit != 0 {
+                idx += 2;
+            }
+        }
+        self.offsets[instance.pattern] + idx
+    }
+
+    // Indices into weights() of every table entry used to evaluate the board.
+    pub fn active_weights(&self, board: &Board) -> Vec<usize> {
+        let (x, o) = (board.get_x(), board.get_o());
+        let base = self.phase(board) * self.phase_size;
+        self.instances
+            .iter()
+            .map(|instance| base + self.index(instance, x, o))
+            .collect()
+    }
+}
+
+impl Default for PatternEval {
+    fn default() -> Self {
+        Self::new(DEFAULT_PHASES)
+    }
+}
+
+impl Evaluator for PatternEval {
+    fn eval(&self, board: &Board) -> i32 {
+        if board.is_over() {
+            return final_score(board.get_x().count_ones(), board.get_o().count_ones());
+        }
+
+        let (x, o) = (board.get_x(), board.get_o());
+        let table = &self.weights[self.phase(board) * self.phase_size..];
+        self.instances
+            .iter()
+            .map(|instance| table[self.index(instance, x, o)] as i32)
+            .sum()
+    }
+}
@@ -0,0 +1,48 @@
+use crate::eval::{ConfigEval, Evaluator, GoodEval, SimpleEval, TaperedEval};
+use std::fmt;
+use std::io;
+
+// An evaluator named the way files and the command line refer to it: simple, good, strategic,
+// tapered, or the path of a config file for anything else.
+#[derive(Clone, PartialEq, Debug)]
+pub enum EvalSpec {
+    Simple,
+    Good,
+    Strategic,
+    Tapered,
+    Config(String),
+}
+
+impl EvalSpec {
+    pub fn parse(text: &str) -> Self {
+        match text.trim() {
+            "simple" => EvalSpec::Simple,
+            "good" => EvalSpec::Good,
+            "strategic" => EvalSpec::Strategic,
+            "tapered" => EvalSpec::Tapered,
+            path => EvalSpec::Config(path.to_string()),
+        }
+    }
+
+    pub fn build(&self) -> io::Result<Box<dyn Evaluator>> {
+        Ok(match self {
+            EvalSpec::Simple => Box::new(SimpleEval {}),
+            EvalSpec::Good => Box::new(GoodEval::default()),
+            EvalSpec::Strategic => Box::new(ConfigEval::strategic()),
+            EvalSpec::Tapered => Box::new(TaperedEval::default()),
+            EvalSpec::Config(path) => Box::new(ConfigEval::from_file(path)?),
+        })
+    }
+}
+
+impl fmt::Display for EvalSpec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EvalSpec::Simple => write!(f, "simple"),
+            EvalSpec::Good => write!(f, "good"),
+            EvalSpec::Strategic => write!(f, "strategic"),
+            EvalSpec::Tapered => write!(f, "tapered"),
+            EvalSpec::Config(path) => write!(f, "{path}"),
+        }
+    }
+}
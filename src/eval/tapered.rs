@@ -0,0 +1,151 @@
+use crate::board::Board;
+use crate::eval::features::{Context, Feature};
+use crate::eval::tune::{self, Tunable};
+use crate::eval::{Evaluator, Term};
+use std::io;
+use std::path::Path;
+
+pub const PHASES: [&str; 3] = ["opening", "midgame", "endgame"];
+
+// Number of empty squares where each weight set applies fully. In between they are blended linearly.
+const OPENING_EMPTIES: u32 = 60;
+const MIDGAME_EMPTIES: u32 = 30;
+
+// The features of the GoodEval preset.
+const FEATURES: [Feature; 5] = [
+    Feature::Discs,
+    Feature::Mobility,
+    Feature::Corners,
+    Feature::Edges,
+    Feature::Ring,
+];
+const N: usize = FEATURES.len();
+
+// Discs are a liability early on, so the opening prefers mobility over disc count.
+const OPENING: [i32; N] = [-1, 3, 20, 3, -5];
+const MIDGAME: [i32; N] = [0, 2, 20, 3, -5];
+const ENDGAME: [i32; N] = [2, 1, 15, 2, -2];
+
+// Same features as GoodEval with a separate weight set per game phase.
+#[derive(Clone)]
+pub struct TaperedEval {
+    weights: [[i32; N]; 3],
+}
+
+impl TaperedEval {
+    pub const fn new(opening: [i32; N], midgame: [i32; N], endgame: [i32; N]) -> Self {
+        Self {
+            weights: [opening, midgame, endgame],
+        }
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut eval = Self::default();
+        let mut flat = eval.weights.concat();
+        tune::assign_weights(&tune::read_weights(path)?, &names_ref(&names()), &mut flat)?;
+        eval.set_weights(&flat);
+        Ok(eval)
+    }
+
+    // How much of each phase applies, out of MIDGAME_EMPTIES.
+    fn blend(board: &Board) -> [i32; 3] {
+        let empties = (!(board.get_x() | board.get_o())).count_ones();
+        if empties >= MIDGAME_EMPTIES {
+            let opening = empties.min(OPENING_EMPTIES) - MIDGAME_EMPTIES;
+            [opening as i32, (MIDGAME_EMPTIES - opening) as i32, 0]
+        } else {
+            [0, empties as i32, (MIDGAME_EMPTIES - empties) as i32]
+        }
+    }
+}
+
+impl Default for TaperedEval {
+    fn default() -> Self {
+        Self::new(OPENING, MIDGAME, ENDGAME)
+    }
+}
+
+// Weight names are prefixed with their phase, e.g. opening.mobility.
+fn names() -> Vec<String> {
+    PHASES
+        .iter()
+        .flat_map(|phase| {
+            FEATURES
+                .iter()
+                .map(move |f| format!("{phase}.{}", f.name()))
+        })
+        .collect()
+}
+
+fn names_ref(names: &[String]) -> Vec<&str> {
+    names.iter().map(String::as_str).collect()
+}
+
+impl Evaluator for TaperedEval {
+    fn eval(&self, board: &Board) -> i32 {
+        let ctx = Context::new(board);
+        if ctx.is_over() {
+            return ctx.final_score();
+        }
+
+        let features = FEATURES.map(|f| f.value(&ctx));
+        let total: i32 = Self::blend(board)
+            .iter()
+            .zip(&self.weights)
+            .map(|(blend, weights)| {
+                let score: i32 = features.iter().zip(weights).map(|(f, w)| f * w).sum();
+                blend * score
+            })
+            .sum();
+        total / MIDGAME_EMPTIES as i32
+    }
+
+    // Values are scaled by how much of each phase applies, phases that don't apply are left out.
+    fn explain(&self, board: &Board) -> Option<Vec<Term>> {
+        if board.is_over() {
+            return Some(Vec::new());
+        }
+        let names = names();
+        let weights = self.weights.concat();
+        let terms = self
+            .features(board)
+            .into_iter()
+            .map(|(i, value)| Term::new(&names[i], value, weights[i]))
+            .collect();
+        Some(terms)
+    }
+}
+
+impl Tunable for TaperedEval {
+    fn num_weights(&self) -> usize {
+        3 * N
+    }
+
+    fn features(&self, board: &Board) -> Vec<(usize, f64)> {
+        let ctx = Context::new(board);
+        let features = FEATURES.map(|f| f.value(&ctx));
+        let blend = Self::blend(board);
+        (0..3)
+            .flat_map(|phase| (0..N).map(move |i| (phase, i)))
+            .filter(|&(phase, _)| blend[phase] != 0)
+            .map(|(phase, i)| {
+                let scale = blend[phase] as f64 / MIDGAME_EMPTIES as f64;
+                (phase * N + i, scale * features[i] as f64)
+            })
+            .collect()
+    }
+
+    fn weights(&self) -> Vec<i32> {
+        self.weights.concat()
+    }
+
+    fn set_weights(&mut self, weights: &[i32]) {
+        for (phase, chunk) in self.weights.iter_mut().zip(weights.chunks_exact(N)) {
+            phase.copy_from_slice(chunk);
+        }
+    }
+
+    fn save_weights(&self, path: &Path) -> io::Result<()> {
+        tune::write_weights(path, &names_ref(&names()), &self.weights.concat())
+    }
+}
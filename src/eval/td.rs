@@ -0,0 +1,102 @@
+use crate::board::Board;
+use crate::eval::Tunable;
+use crate::player::{Mixed, Negamax, Player};
+
+// Learns evaluator weights from self-play with TD(λ). Every position is moved towards the
+// λ-return: a blend of the evaluations of the positions that followed it and the final result.
+pub struct TdTrainer<E: Tunable + Clone> {
+    eval: E,
+    weights: Vec<f64>,
+    lambda: f64,
+    learning_rate: f64,
+    depth: u8,
+    exploration: f64,
+    games: u64,
+}
+
+impl<E: Tunable + Clone> TdTrainer<E> {
+    pub fn new(eval: E, lambda: f64, learning_rate: f64, depth: u8, exploration: f64) -> Self {
+        let weights = eval.weights().iter().map(|&w| w as f64).collect();
+        Self {
+            eval,
+            weights,
+            lambda,
+            learning_rate,
+            depth,
+            exploration,
+            games: 0,
+        }
+    }
+
+    pub const fn eval(&self) -> &E {
+        &self.eval
+    }
+
+    pub const fn games(&self) -> u64 {
+        self.games
+    }
+
+    fn predict(&self, board: &Board) -> (f64, Vec<(usize, f64)>) {
+        let features = self.eval.features(board);
+        let value = features.iter().map(|&(i, f)| self.weights[i] * f).sum();
+        (value, features)
+    }
+
+    // Both sides search with the current weights and play a random move with the exploration chance.
+    fn self_play(&self, seed: u64) -> (Vec<Board>, f64) {
+        let mut x = Mixed::new(
+            Negamax::new('x', self.depth, self.eval.clone()),
+            'x',
+            seed,
+            self.exploration,
+        );
+        let mut o = Mixed::new(
+            Negamax::new('o', self.depth, self.eval.clone()),
+            'o',
+            !seed,
+            self.exploration,
+        );
+
+        let mut board = Board::new();
+        let mut x_turn = true;
+        let mut positions = Vec::new();
+        while !board.is_over() {
+            positions.push(board.clone());
+            let mv = if x_turn {
+                x.get_move(board.clone())
+            } else {
+                o.get_move(board.clone())
+            };
+            if let Some(mv) = mv {
+                board.apply_move(mv, x_turn);
+            }
+            x_turn = !x_turn;
+        }
+
+        let result = board.get_x().count_ones() as f64 - board.get_o().count_ones() as f64;
+        (positions, result)
+    }
+
+    // Plays one game and returns its final disc differential.
+    pub fn train_game(&mut self, seed: u64) -> f64 {
+        let (positions, result) = self.self_play(seed);
+
+        // Walk backwards so each λ-return can be built from the next one.
+        let mut ret = result;
+        let mut next_value = result;
+        for board in positions.iter().rev() {
+            ret = (1.0 - self.lambda) * next_value + self.lambda * ret;
+            let (value, features) = self.predict(board);
+            let error = ret - value;
+            for (i, f) in features {
+                self.weights[i] += self.learning_rate * error * f;
+            }
+            next_value = value;
+        }
+
+        let rounded: Vec<i32> = self.weights.iter().map(|w| w.round() as i32).collect();
+        self.eval.set_weights(&rounded);
+        self.games += 1;
+        result
+    }
+}
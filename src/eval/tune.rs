@@ -0,0 +1,236 @@
+use crate::board::Board;
+use crate::eval::{Evaluator, PatternEval};
+use crate::utils::invalid_data;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+// An evaluator that is a linear function of its features, so its weights can be fitted.
+// Features are sparse (weight index, value) pairs from x's perspective.
+pub trait Tunable: Evaluator {
+    fn num_weights(&self) -> usize;
+    fn features(&self, board: &Board) -> Vec<(usize, f64)>;
+    fn weights(&self) -> Vec<i32>;
+    fn set_weights(&mut self, weights: &[i32]);
+    fn save_weights(&self, path: &Path) -> io::Result<()>;
+}
+
+impl Tunable for PatternEval {
+    fn num_weights(&self) -> usize {
+        PatternEval::weights(self).len()
+    }
+
+    fn features(&self, board: &Board) -> Vec<(usize, f64)> {
+        self.active_weights(board)
+            .into_iter()
+            .map(|idx| (idx, 1.0))
+            .collect()
+    }
+
+    fn weights(&self) -> Vec<i32> {
+        PatternEval::weights(self)
+            .iter()
+            .map(|&w| w as i32)
+            .collect()
+    }
+
+    fn set_weights(&mut self, weights: &[i32]) {
+        for (w, &new) in self.weights_mut().iter_mut().zip(weights) {
+            *w = new.clamp(i16::MIN as i32, i16::MAX as i32) as i16;
+        }
+    }
+
+    fn save_weights(&self, path: &Path) -> io::Result<()> {
+        self.save(path)
+    }
+}
+
+// Weight files are plain text with one "name value" pair per line.
+pub fn read_weights(path: impl AsRef<Path>) -> io::Result<Vec<(String, i32)>> {
+    parse_weights(&fs::read_to_string(path)?)
+}
+
+pub fn parse_weights(text: &str) -> io::Result<Vec<(String, i32)>> {
+    let mut weights = Vec::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut parts = line.split_whitespace();
+        let (Some(name), Some(value), None) = (parts.next(), parts.next(), parts.next()) else {
+            return Err(invalid_data(format!("malformed weight line: {line}")));
+        };
+        let value = value
+            .parse()
+            .map_err(|_| invalid_data(format!("invalid weight: {line}")))?;
+        weights.push((name.to_string(), value));
+    }
+    Ok(weights)
+}
+
+pub fn write_weights(path: impl AsRef<Path>, names: &[&str], weights: &[i32]) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    for (name, weight) in names.iter().zip(weights) {
+        writeln!(writer, "{name} {weight}")?;
+    }
+    writer.flush()
+}
+
+// Weights missing from the file keep their current value.
+pub(crate) fn assign_weights(
+    read: &[(String, i32)],
+    names: &[&str],
+    weights: &mut [i32],
+) -> io::Result<()> {
+    for (name, value) in read {
+        let Some(idx) = names.iter().position(|n| n == name) else {
+            return Err(invalid_data(format!("unknown feature: {name}")));
+        };
+        weights[idx] = *value;
+    }
+    Ok(())
+}
+
+pub struct Sample {
+    pub board: Board,
+    pub target: f64,
+}
+
+// Datasets hold one position per line - the board in compact form followed by its score for x,
+// either the final disc differential or a deep search score.
+pub fn read_dataset(path: impl AsRef<Path>) -> io::Result<Vec<Sample>> {
+    let mut samples = Vec::new();
+    for line in fs::read_to_string(path)?.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut parts = line.split_whitespace();
+        let board = parts.next().and_then(Board::from_compact);
+        let target = parts.next().and_then(|t| t.parse().ok());
+        let (Some(board), Some(target)) = (board, target) else {
+            return Err(invalid_data(format!("malformed sample: {line}")));
+        };
+        samples.push(Sample { board, target });
+    }
+    Ok(samples)
+}
+
+pub fn write_dataset(path: impl AsRef<Path>, samples: &[Sample]) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    for sample in samples {
+        writeln!(writer, "{} {}", sample.board.to_compact(), sample.target)?;
+    }
+    writer.flush()
+}
+
+fn predict(features: &[(usize, f64)], weights: &[f64]) -> f64 {
+    features.iter().map(|&(i, f)| weights[i] * f).sum()
+}
+
+fn store<E: Tunable>(eval: &mut E, weights: &[f64]) {
+    let rounded: Vec<i32> = weights.iter().map(|w| w.round() as i32).collect();
+    eval.set_weights(&rounded);
+}
+
+pub fn mean_squared_error<E: Tunable>(eval: &E, samples: &[Sample]) -> f64 {
+    let weights: Vec<f64> = eval.weights().iter().map(|&w| w as f64).collect();
+    let total: f64 = samples
+        .iter()
+        .map(|s| (predict(&eval.features(&s.board), &weights) - s.target).powi(2))
+        .sum();
+    total / samples.len().max(1) as f64
+}
+
+// The most weights least_squares accepts, its matrix has the square of this many entries.
+pub const MAX_LEAST_SQUARES_WEIGHTS: usize = 4096;
+
+// Solves the normal equations directly. Only suitable for evaluators with a handful of weights,
+// larger ones are rejected rather than allocating the matrix.
+pub fn least_squares<E: Tunable>(eval: &mut E, samples: &[Sample]) -> io::Result<f64> {
+    let n = eval.num_weights();
+    if n > MAX_LEAST_SQUARES_WEIGHTS {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "{n} weights are too many for least squares, the limit is \
+                 {MAX_LEAST_SQUARES_WEIGHTS}"
+            ),
+        ));
+    }
+    let mut ata = vec![vec![0.0; n]; n];
+    let mut atb = vec![0.0; n];
+
+    for sample in samples {
+        let features = eval.features(&sample.board);
+        for &(i, fi) in &features {
+            atb[i] += fi * sample.target;
+            for &(j, fj) in &features {
+                ata[i][j] += fi * fj;
+            }
+        }
+    }
+
+    // A tiny ridge term keeps features that never occur from making the system singular.
+    for (i, row) in ata.iter_mut().enumerate() {
+        row[i] += 1e-6;
+    }
+
+    let weights = solve(ata, atb);
+    store(eval, &weights);
+    Ok(mean_squared_error(eval, samples))
+}
+
+// Gaussian elimination with partial pivoting.
+fn solve(mut a: Vec<Vec<f64>>, mut b: Vec<f64>) -> Vec<f64> {
+    let n = b.len();
+    for col in 0..n {
+        let pivot = (col..n)
+            .max_by(|&r1, &r2| a[r1][col].abs().total_cmp(&a[r2][col].abs()))
+            .unwrap();
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+
+        let (top, bottom) = a.split_at_mut(col + 1);
+        let pivot_row = &top[col];
+        for (offset, row) in bottom.iter_mut().enumerate() {
+            let factor = row[col] / pivot_row[col];
+            for (value, pivot) in row[col..].iter_mut().zip(&pivot_row[col..]) {
+                *value -= factor * pivot;
+            }
+            b[col + 1 + offset] -= factor * b[col];
+        }
+    }
+
+    let mut x = vec![0.0; n];
+    for row in (0..n).rev() {
+        let rest: f64 = (row + 1..n).map(|k| a[row][k] * x[k]).sum();
+        x[row] = (b[row] - rest) / a[row][row];
+    }
+    x
+}
+
+// Stochastic gradient descent starting from the evaluator's current weights.
+pub fn gradient_descent<E: Tunable>(
+    eval: &mut E,
+    samples: &[Sample],
+    epochs: usize,
+    learning_rate: f64,
+) -> f64 {
+    let mut weights: Vec<f64> = eval.weights().iter().map(|&w| w as f64).collect();
+    let features: Vec<Vec<(usize, f64)>> =
+        samples.iter().map(|s| eval.features(&s.board)).collect();
+
+    for _ in 0..epochs {
+        for (sample, features) in samples.iter().zip(&features) {
+            let error = predict(features, &weights) - sample.target;
+            for &(i, f) in features {
+                weights[i] -= learning_rate * error * f;
+            }
+        }
+    }
+
+    store(eval, &weights);
+    mean_squared_error(eval, samples)
+}
@@ -1,79 +1,511 @@
 use crate::board::Board;
-use crate::player::Player;
+use crate::clock::{Clock, TimeControl};
+use crate::observer::GameObserver;
+use crate::player::{Action, Player};
+use crate::save::SavedGame;
+use std::fmt;
+use std::io;
+use std::time::{Duration, Instant};
 
+// One turn of a game. The eval is the mover's own score for the move and nodes the positions
+// it searched, if its player reports them.
 #[derive(Clone)]
+pub struct Ply {
+    pub mv: Option<u64>, // None is a pass
+    pub eval: Option<i32>,
+    pub nodes: Option<u64>,
+    pub time: Duration,
+}
+
+impl Ply {
+    pub const fn new(mv: Option<u64>) -> Self {
+        Self {
+            mv,
+            eval: None,
+            nodes: None,
+            time: Duration::ZERO,
+        }
+    }
+}
+
 pub struct Game<P1: Player, P2: Player> {
     board: Board,
     p1: P1,
     p2: P2,
     x_turn: bool,
+    start: Board,
+    start_x_turn: bool,
+    history: Vec<Ply>,
+    undone: Vec<Ply>,
+    clock: Option<Clock>,
+    termination: Termination,
+    observers: Vec<Box<dyn GameObserver>>,
+}
+
+// Observers can't be copied, a clone of a game plays on without any.
+impl<P1: Player + Clone, P2: Player + Clone> Clone for Game<P1, P2> {
+    fn clone(&self) -> Self {
+        Self {
+            board: self.board.clone(),
+            p1: self.p1.clone(),
+            p2: self.p2.clone(),
+            x_turn: self.x_turn,
+            start: self.start.clone(),
+            start_x_turn: self.start_x_turn,
+            history: self.history.clone(),
+            undone: self.undone.clone(),
+            clock: self.clock.clone(),
+            termination: self.termination,
+            observers: Vec::new(),
+        }
+    }
 }
 
 impl<P1: Player, P2: Player> Game<P1, P2> {
     pub fn new(p1: P1, p2: P2) -> Self {
-        Self {
-            board: Board::default(),
+        Self::from_position(Board::default(), true, p1, p2)
+    }
+
+    // A game from any position with either side to move.
+    pub fn from_position(board: Board, x_turn: bool, p1: P1, p2: P2) -> Self {
+        let mut game = Self {
+            board: Board::new(),
             p1, // x
             p2, // o
             x_turn: true,
-        }
+            start: Board::new(),
+            start_x_turn: true,
+            history: Vec::new(),
+            undone: Vec::new(),
+            clock: None,
+            termination: Termination::Finished,
+            observers: Vec::new(),
+        };
+        game.set_start(board, x_turn);
+        game
+    }
+
+    // Plays with clocks from the next game on, None plays without.
+    pub fn set_time_control(&mut self, control: Option<TimeControl>) {
+        self.clock = control.map(Clock::new);
+    }
+
+    // Replaces the clock, keeping each side's remaining time. Loading a record resets it.
+    pub fn set_clock(&mut self, clock: Option<Clock>) {
+        self.clock = clock;
+    }
+
+    pub const fn clock(&self) -> Option<&Clock> {
+        self.clock.as_ref()
     }
 
-    pub fn run(&mut self, print: bool) -> Score {
+    // Plays until the game is over, a side runs out of time or a side forfeits by playing an
+    // illegal move or passing while it has moves, telling the observers what happens.
+    pub fn run(&mut self) -> GameRecord {
+        for observer in &mut self.observers {
+            observer.on_start(&self.board, self.x_turn);
+        }
+        if self.board.is_over() {
+            return self.finish();
+        }
         loop {
-            if print {
-                println!();
-                self.board.print(self.x_turn);
+            let remaining = self
+                .clock
+                .as_ref()
+                .map(|clock| clock.remaining(self.x_turn));
+            for observer in &mut self.observers {
+                observer.on_turn(&self.board, self.x_turn, remaining);
             }
 
-            if self.x_turn && print {
-                println!("X to move");
-            } else if print {
-                println!("O to move");
+            if let Some(clock) = &self.clock {
+                let (control, remaining) = (clock.control(), clock.remaining(self.x_turn));
+                if self.x_turn {
+                    self.p1.set_clock(control, remaining);
+                } else {
+                    self.p2.set_clock(control, remaining);
+                }
             }
 
-            let mv = if self.x_turn {
-                self.p1.get_move(self.board.clone())
+            let start = Instant::now();
+            let (action, eval, nodes) = if self.x_turn {
+                let action = self.p1.get_action(self.board.clone());
+                (action, self.p1.last_eval(), self.p1.last_nodes())
             } else {
-                self.p2.get_move(self.board.clone())
+                let action = self.p2.get_action(self.board.clone());
+                (action, self.p2.last_eval(), self.p2.last_nodes())
             };
+            let time = start.elapsed();
 
-            match mv {
-                Some(mv) => self.board.apply_move(mv, self.x_turn),
-                None => {
-                    if print {
-                        println!("No moves available - skipping turn")
+            // Only moves and passes earn the increment, saving or taking back just uses time.
+            let moved = matches!(action, Action::Move(_) | Action::Pass);
+            if let Some(clock) = &mut self.clock
+                && !(if moved {
+                    clock.spend(self.x_turn, time)
+                } else {
+                    clock.charge(self.x_turn, time)
+                })
+            {
+                let symbol = if self.x_turn { 'x' } else { 'o' };
+                self.termination = Termination::Timeout(symbol);
+                return self.finish();
+            }
+
+            let legal = self.board.legal_moves(self.x_turn);
+            match action {
+                Action::Move(mv) if mv.count_ones() == 1 && legal & mv != 0 => {
+                    let x_turn = self.x_turn;
+                    let flips = self.play(Ply {
+                        mv: Some(mv),
+                        eval,
+                        nodes,
+                        time,
+                    });
+                    for observer in &mut self.observers {
+                        observer.on_move(&self.board, mv, flips, x_turn);
+                    }
+                }
+                Action::Pass if legal == 0 => {
+                    for observer in &mut self.observers {
+                        observer.on_pass(&self.board, self.x_turn);
+                    }
+                    self.play(Ply {
+                        mv: None,
+                        eval: None,
+                        nodes: None,
+                        time,
+                    });
+                }
+                Action::Move(mv) => return self.forfeit(Violation::IllegalMove(mv)),
+                Action::Pass => return self.forfeit(Violation::IllegalPass),
+                Action::Undo => self.take_back(Game::undo, "Nothing to undo"),
+                Action::Redo => self.take_back(Game::redo, "Nothing to redo"),
+                Action::Save(path) => {
+                    let message = match self.save(&path) {
+                        Ok(()) => format!("Saved the game to {path}"),
+                        Err(err) => format!("Could not save {path}: {err}"),
+                    };
+                    for observer in &mut self.observers {
+                        observer.on_message(&message);
                     }
                 }
             }
 
-            self.x_turn = !self.x_turn;
-
             if self.board.is_over() {
-                if print {
-                    self.board.print(self.x_turn);
-                }
+                return self.finish();
+            }
+        }
+    }
 
-                return Score::new(
-                    self.board.get_x().count_ones(),
-                    self.board.get_o().count_ones(),
-                );
+    // Attaches an observer to every game this plays from now on.
+    pub fn add_observer(&mut self, observer: impl GameObserver + 'static) {
+        self.observers.push(Box::new(observer));
+    }
+
+    fn finish(&mut self) -> GameRecord {
+        let record = self.record();
+        for observer in &mut self.observers {
+            observer.on_end(&self.board, &record);
+        }
+        record
+    }
+
+    // The side to move broke the rules and loses the game.
+    fn forfeit(&mut self, violation: Violation) -> GameRecord {
+        for observer in &mut self.observers {
+            observer.on_illegal_move(&self.board, violation, self.x_turn);
+        }
+        let symbol = if self.x_turn { 'x' } else { 'o' };
+        self.termination = Termination::Forfeit(symbol, violation);
+        self.finish()
+    }
+
+    // Steps through the history until the player who asked is to move again,
+    // so taking back a move against an engine also takes back the engine's reply.
+    fn take_back(&mut self, step: fn(&mut Self) -> bool, nothing: &str) {
+        let side = self.x_turn;
+        if !step(self) {
+            for observer in &mut self.observers {
+                observer.on_message(nothing);
             }
+            return;
+        }
+        while self.x_turn != side && step(self) {}
+        for observer in &mut self.observers {
+            observer.on_take_back(&self.board, self.x_turn);
+        }
+    }
+
+    // Writes the game so far with both players, which must have been built from specs.
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        let (Some(x), Some(o)) = (self.p1.spec(), self.p2.spec()) else {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "these players can't be saved",
+            ));
+        };
+        let mut saved = SavedGame::new(x, o, self.record());
+        saved.clock = self.clock.clone();
+        saved.save(path)
+    }
+
+    // Plays a move or a pass and forgets any undone moves. Returns the discs the move flipped.
+    pub fn play(&mut self, ply: Ply) -> u64 {
+        self.undone.clear();
+        self.apply(ply)
+    }
+
+    fn apply(&mut self, ply: Ply) -> u64 {
+        let mut flips = 0;
+        if let Some(mv) = ply.mv {
+            let opp = |board: &Board| {
+                if self.x_turn {
+                    board.get_o()
+                } else {
+                    board.get_x()
+                }
+            };
+            let before = opp(&self.board);
+            self.board.apply_move(mv, self.x_turn);
+            flips = before & !opp(&self.board);
+        }
+        self.history.push(ply);
+        self.x_turn = !self.x_turn;
+        flips
+    }
+
+    pub fn undo(&mut self) -> bool {
+        let Some(ply) = self.history.pop() else {
+            return false;
+        };
+        self.undone.push(ply);
+        (self.board, self.x_turn) = self.position_at(self.history.len()).unwrap();
+        true
+    }
+
+    pub fn redo(&mut self) -> bool {
+        let Some(ply) = self.undone.pop() else {
+            return false;
+        };
+        self.apply(ply);
+        true
+    }
+
+    // The board and side to move after the given number of plies, passes included.
+    pub fn position_at(&self, ply: usize) -> Option<(Board, bool)> {
+        let plies = self.history.get(..ply)?;
+        Some(replay(&self.start, self.start_x_turn, plies))
+    }
+
+    pub const fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub const fn x_turn(&self) -> bool {
+        self.x_turn
+    }
+
+    pub fn ply(&self) -> usize {
+        self.history.len()
+    }
+
+    pub fn history(&self) -> &[Ply] {
+        &self.history
+    }
+
+    pub fn record(&self) -> GameRecord {
+        GameRecord {
+            start: self.start.clone(),
+            start_x_turn: self.start_x_turn,
+            moves: self.history.clone(),
+            score: Score::with_termination(
+                self.board.get_x().count_ones(),
+                self.board.get_o().count_ones(),
+                self.termination,
+            ),
+        }
+    }
+
+    // Sets the game up at the start of a record, its moves can then be stepped through with redo.
+    pub fn load_record(&mut self, record: &GameRecord) {
+        self.set_start(record.start.clone(), record.start_x_turn);
+        self.undone = record.moves.iter().rev().cloned().collect();
+    }
+
+    // Starts a new game from the position, keeping the players and the time control.
+    pub fn set_start(&mut self, board: Board, x_turn: bool) {
+        assert_eq!(
+            board.get_x() & board.get_o(),
+            0,
+            "a square can't hold both colours"
+        );
+        self.board = board.clone();
+        self.x_turn = x_turn;
+        self.start = board;
+        self.start_x_turn = x_turn;
+        self.history.clear();
+        self.undone.clear();
+        self.termination = Termination::Finished;
+        if let Some(clock) = &self.clock {
+            self.clock = Some(Clock::new(clock.control()));
         }
     }
+
+    // Starts again from the position the game started from.
     pub fn reset(&mut self) {
-        self.board = Board::new();
+        self.set_start(self.start.clone(), self.start_x_turn);
     }
 }
 
+// Everything needed to replay a game: where it started and every ply.
+#[derive(Clone)]
+pub struct GameRecord {
+    start: Board,
+    start_x_turn: bool,
+    moves: Vec<Ply>,
+    score: Score,
+}
+
+impl GameRecord {
+    pub const fn new(start: Board, start_x_turn: bool, moves: Vec<Ply>, score: Score) -> Self {
+        Self {
+            start,
+            start_x_turn,
+            moves,
+            score,
+        }
+    }
+
+    pub const fn start(&self) -> &Board {
+        &self.start
+    }
+
+    pub const fn start_x_turn(&self) -> bool {
+        self.start_x_turn
+    }
+
+    pub fn moves(&self) -> &[Ply] {
+        &self.moves
+    }
+
+    pub const fn score(&self) -> &Score {
+        &self.score
+    }
+
+    // The board and side to move after the given number of plies.
+    pub fn position_at(&self, ply: usize) -> Option<(Board, bool)> {
+        let plies = self.moves.get(..ply)?;
+        Some(replay(&self.start, self.start_x_turn, plies))
+    }
+
+    // Time the players took for all their moves.
+    pub fn time(&self) -> Duration {
+        self.moves.iter().map(|ply| ply.time).sum()
+    }
+
+    // Positions searched for all the moves, by players that report it.
+    pub fn nodes(&self) -> u64 {
+        self.moves.iter().filter_map(|ply| ply.nodes).sum()
+    }
+
+    // Every position of the game with its side to move, from the start to the final board.
+    pub fn positions(&self) -> Vec<(Board, bool)> {
+        let mut positions = vec![(self.start.clone(), self.start_x_turn)];
+        for ply in &self.moves {
+            let (mut board, x_turn) = positions[positions.len() - 1].clone();
+            if let Some(mv) = ply.mv {
+                board.apply_move(mv, x_turn);
+            }
+            positions.push((board, !x_turn));
+        }
+        positions
+    }
+}
+
+fn replay(start: &Board, start_x_turn: bool, plies: &[Ply]) -> (Board, bool) {
+    let mut board = start.clone();
+    let mut x_turn = start_x_turn;
+    for ply in plies {
+        if let Some(mv) = ply.mv {
+            board.apply_move(mv, x_turn);
+        }
+        x_turn = !x_turn;
+    }
+    (board, x_turn)
+}
+
+// How a game ended. A timeout names the side that ran out of time and a forfeit the side that
+// broke the rules, which loses whatever the board says.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Termination {
+    Finished,
+    Timeout(char),
+    Forfeit(char, Violation),
+}
+
+impl Termination {
+    // The side that lost without the game being played out.
+    pub const fn loser(&self) -> Option<char> {
+        match *self {
+            Termination::Finished => None,
+            Termination::Timeout(symbol) | Termination::Forfeit(symbol, _) => Some(symbol),
+        }
+    }
+}
+
+impl fmt::Display for Termination {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Termination::Finished => write!(f, "played to the end"),
+            Termination::Timeout(symbol) => {
+                write!(f, "{} ran out of time", symbol.to_ascii_uppercase())
+            }
+            Termination::Forfeit(symbol, violation) => {
+                write!(f, "{} forfeited: {violation}", symbol.to_ascii_uppercase())
+            }
+        }
+    }
+}
+
+// A broken rule that forfeits the game.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Violation {
+    IllegalMove(u64),
+    IllegalPass,
+    // Gave the game up, which is all a GGF result says about a forfeit.
+    Resigned,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Violation::IllegalMove(mv) if mv.count_ones() == 1 => {
+                write!(f, "illegal move {}", Board::square_name(mv))
+            }
+            Violation::IllegalMove(mv) => write!(f, "invalid move {mv:#018x}"),
+            Violation::IllegalPass => write!(f, "passed with moves available"),
+            Violation::Resigned => write!(f, "resigned"),
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct Score {
     x: u32,
     o: u32,
+    termination: Termination,
 }
 
 impl Score {
     pub const fn new(x: u32, o: u32) -> Self {
-        Self { x, o }
+        Self::with_termination(x, o, Termination::Finished)
+    }
+
+    pub const fn with_termination(x: u32, o: u32, termination: Termination) -> Self {
+        Self { x, o, termination }
+    }
+
+    pub const fn termination(&self) -> Termination {
+        self.termination
     }
 
     pub const fn x(&self) -> u32 {
@@ -85,6 +517,13 @@ impl Score {
     }
 
     pub const fn outcome(&self) -> Outcome {
+        if let Some(symbol) = self.termination.loser() {
+            return if symbol == 'x' {
+                Outcome::OWin
+            } else {
+                Outcome::Xwin
+            };
+        }
         if self.x > self.o {
             Outcome::Xwin
         } else if self.o > self.x {
@@ -0,0 +1,470 @@
+use crate::board::Board;
+use crate::game::{GameRecord, Ply, Score, Termination, Violation};
+use crate::utils::invalid_data;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+// A game in the Generic Game Format used by online servers and archives, for example
+// (;GM[Othello]PB[alice]PW[bob]TY[8]RE[+4.000]BO[8 <8 rows of squares> *]B[f5//1.20]W[d6/-2/0.80];)
+// Black is x and moves first from the usual start. Properties not kept here are skipped on reading.
+#[derive(Clone)]
+pub struct GgfGame {
+    pub black: String,
+    pub white: String,
+    pub black_rating: Option<f64>,
+    pub white_rating: Option<f64>,
+    pub place: String,
+    pub date: String,
+    pub time_control: String,
+    // Disc difference for black as written by the server, it can differ from the final board
+    // when a game ended by resignation or on time.
+    pub result: Option<f64>,
+    // How a game ended early, from the suffix of the result: t on time, r by resignation.
+    pub result_suffix: Option<char>,
+    pub record: GameRecord,
+}
+
+impl GgfGame {
+    // Games lost on time or by forfeit are written as a full win for the other side with the
+    // suffix t or r, the way servers record games that ended early.
+    pub fn from_record(record: GameRecord, black: &str, white: &str) -> Self {
+        let score = record.score();
+        let (result, result_suffix) = match score.termination() {
+            Termination::Finished => (score.x() as f64 - score.o() as f64, None),
+            termination => {
+                let result = if termination.loser() == Some('x') {
+                    -64.0
+                } else {
+                    64.0
+                };
+                let suffix = match termination {
+                    Termination::Timeout(_) => 't',
+                    _ => 'r',
+                };
+                (result, Some(suffix))
+            }
+        };
+        Self {
+            black: black.to_string(),
+            white: white.to_string(),
+            black_rating: None,
+            white_rating: None,
+            place: String::new(),
+            date: String::new(),
+            time_control: String::new(),
+            result: Some(result),
+            result_suffix,
+            record,
+        }
+    }
+
+    // Parses every game in the text, checking each move against the board.
+    pub fn parse_all(text: &str) -> io::Result<Vec<Self>> {
+        let mut games = Vec::new();
+        let mut rest = text;
+        while let Some(start) = rest.find("(;") {
+            let (properties, remaining) = split_properties(&rest[start + 2..])?;
+            games.push(Self::from_properties(&properties)?);
+            rest = remaining;
+        }
+        Ok(games)
+    }
+
+    pub fn read_file(path: impl AsRef<Path>) -> io::Result<Vec<Self>> {
+        Self::parse_all(&fs::read_to_string(path)?)
+    }
+
+    pub fn write_file(path: impl AsRef<Path>, games: &[Self]) -> io::Result<()> {
+        let text: String = games.iter().map(|game| format!("{game}\n")).collect();
+        fs::write(path, text)
+    }
+
+    fn from_properties(properties: &[(&str, &str)]) -> io::Result<Self> {
+        let mut start = (Board::new(), true);
+        let mut moves = Vec::new();
+        for &(name, value) in properties {
+            match name {
+                "GM" if !value.eq_ignore_ascii_case("othello") => {
+                    return Err(invalid_data(format!("not an Othello game: {value}")));
+                }
+                // Variants like 8r only change how the start was chosen.
+                "TY" if value.trim_end_matches(|c: char| c.is_ascii_alphabetic()) != "8" => {
+                    return Err(invalid_data(format!("unsupported board type: {value}")));
+                }
+                "BO" => start = parse_start(value)?,
+                "B" | "W" => moves.push((name == "B", parse_move(value)?)),
+                _ => {}
+            }
+        }
+
+        let property = |name: &str| {
+            properties
+                .iter()
+                .find(|&&(n, _)| n == name)
+                .map_or("", |&(_, value)| value)
+        };
+        // Results may carry a suffix like :r for resignation.
+        let result: Option<f64> = property("RE")
+            .split(':')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .parse()
+            .ok();
+        let result_suffix = property("RE")
+            .split_once(':')
+            .and_then(|(_, suffix)| suffix.trim().chars().next());
+        // Games that ended early were lost by the side the result goes against.
+        let loser = match result {
+            Some(result) if result < 0.0 => Some('x'),
+            Some(result) if result > 0.0 => Some('o'),
+            _ => None,
+        };
+        let termination = match (result_suffix, loser) {
+            (Some('t'), Some(loser)) => Termination::Timeout(loser),
+            (Some('r'), Some(loser)) => Termination::Forfeit(loser, Violation::Resigned),
+            _ => Termination::Finished,
+        };
+        Ok(Self {
+            black: property("PB").to_string(),
+            white: property("PW").to_string(),
+            black_rating: property("RB").trim().parse().ok(),
+            white_rating: property("RW").trim().parse().ok(),
+            place: property("PC").to_string(),
+            date: property("DT").to_string(),
+            time_control: property("TI").to_string(),
+            result,
+            result_suffix,
+            record: replay(start.0, start.1, moves, termination)?,
+        })
+    }
+}
+
+impl fmt::Display for GgfGame {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "(;GM[Othello]")?;
+        for (name, value) in [
+            ("PC", &self.place),
+            ("DT", &self.date),
+            ("PB", &self.black),
+            ("PW", &self.white),
+        ] {
+            if !value.is_empty() {
+                write!(f, "{name}[{value}]")?;
+            }
+        }
+        for (name, rating) in [("RB", self.black_rating), ("RW", self.white_rating)] {
+            if let Some(rating) = rating {
+                write!(f, "{name}[{rating:.2}]")?;
+            }
+        }
+        if !self.time_control.is_empty() {
+            write!(f, "TI[{}]", self.time_control)?;
+        }
+        write!(f, "TY[8]")?;
+        if let Some(result) = self.result {
+            match self.result_suffix {
+                Some(suffix) => write!(f, "RE[{result:+.3}:{suffix}]")?,
+                None => write!(f, "RE[{result:+.3}]")?,
+            }
+        }
+
+        let record = &self.record;
+        let side = if record.start_x_turn() { '*' } else { 'O' };
+        write!(f, "BO[8 {} {side}]", format_board(record.start()))?;
+
+        let mut x_turn = record.start_x_turn();
+        for ply in record.moves() {
+            let mv = ply.mv.map_or("PA".to_string(), Board::square_name);
+            let eval = ply.eval.map_or(String::new(), |eval| eval.to_string());
+            write!(
+                f,
+                "{}[{mv}/{eval}/{:.2}]",
+                if x_turn { 'B' } else { 'W' },
+                ply.time.as_secs_f64()
+            )?;
+            x_turn = !x_turn;
+        }
+        write!(f, ";)")
+    }
+}
+
+// Splits NAME[value] pairs up to the closing ;) and returns what follows the game.
+fn split_properties(text: &str) -> io::Result<(Vec<(&str, &str)>, &str)> {
+    let mut properties = Vec::new();
+    let mut rest = text.trim_start();
+    loop {
+        if let Some(remaining) = rest.strip_prefix(";)") {
+            return Ok((properties, remaining));
+        }
+        let name_len = rest
+            .find(|c: char| !c.is_ascii_uppercase())
+            .unwrap_or(rest.len());
+        let (name, after) = rest.split_at(name_len);
+        let Some(after) = after.strip_prefix('[') else {
+            return Err(invalid_data(
+                "expected a property or the end of the game".to_string(),
+            ));
+        };
+        let Some(end) = after.find(']') else {
+            return Err(invalid_data(format!("unterminated property {name}")));
+        };
+        properties.push((name, &after[..end]));
+        rest = after[end + 1..].trim_start();
+    }
+}
+
+// Squares are listed from a1 to h8 rank by rank, * for black, O for white and - for empty,
+// followed by the side to move. Servers write the board as eight rows separated by spaces.
+fn parse_start(value: &str) -> io::Result<(Board, bool)> {
+    let parts: Vec<&str> = value.split_whitespace().collect();
+    let [size, rows @ .., side] = &parts[..] else {
+        return Err(invalid_data(format!("invalid board: {value}")));
+    };
+    let squares = rows.concat();
+    if *size != "8" || squares.chars().count() != 64 {
+        return Err(invalid_data(format!("invalid board: {value}")));
+    }
+
+    let mut x = 0;
+    let mut o = 0;
+    for (idx, c) in squares.chars().enumerate() {
+        let square = square_at(idx);
+        match c {
+            '*' | 'x' | 'X' => x |= square,
+            'O' | 'o' => o |= square,
+            '-' | '.' => {}
+            _ => return Err(invalid_data(format!("invalid square {c} in board"))),
+        }
+    }
+    let x_turn = match *side {
+        "*" => true,
+        "O" => false,
+        _ => return Err(invalid_data(format!("invalid side to move: {side}"))),
+    };
+    Ok((Board::from_bitboards(x, o), x_turn))
+}
+
+// The idx-th square in GGF order, which starts at a1 rather than a8.
+fn square_at(idx: usize) -> u64 {
+    Board::get_move(7 - idx as u8 / 8, idx as u8 % 8)
+}
+
+// Written as eight rows like the servers do.
+fn format_board(board: &Board) -> String {
+    let squares: Vec<char> = (0..64)
+        .map(|idx| {
+            let square = square_at(idx);
+            if board.get_x() & square != 0 {
+                '*'
+            } else if board.get_o() & square != 0 {
+                'O'
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let rows: Vec<String> = squares.chunks(8).map(|row| row.iter().collect()).collect();
+    rows.join(" ")
+}
+
+// A move is written as square/eval/time where the last two are optional, PA is a pass.
+fn parse_move(value: &str) -> io::Result<Ply> {
+    let mut parts = value.split('/');
+    let square = parts.next().unwrap_or_default().trim();
+    let mv = if square.eq_ignore_ascii_case("pa") || square.eq_ignore_ascii_case("pass") {
+        None
+    } else {
+        let mv = Board::parse_square(square)
+            .ok_or_else(|| invalid_data(format!("invalid move: {value}")))?;
+        Some(mv)
+    };
+    let eval = parts
+        .next()
+        .and_then(|eval| eval.trim().parse::<f64>().ok())
+        .map(|eval| eval.round() as i32);
+    let time = match parts.next().map(str::trim) {
+        Some(time) if !time.is_empty() => {
+            parse_time(time).ok_or_else(|| invalid_data(format!("invalid time: {value}")))?
+        }
+        _ => Duration::ZERO,
+    };
+    Ok(Ply {
+        mv,
+        eval,
+        nodes: None,
+        time,
+    })
+}
+
+// Times are seconds, optionally with minutes and hours in front like 1:05.5.
+fn parse_time(value: &str) -> Option<Duration> {
+    let mut seconds = 0.0;
+    for part in value.split(':') {
+        let part: f64 = part.parse().ok()?;
+        if part < 0.0 {
+            return None;
+        }
+        seconds = seconds * 60.0 + part;
+    }
+    Duration::try_from_secs_f64(seconds).ok()
+}
+
+// Plays the moves from the start, adding the passes some records leave out.
+fn replay(
+    start: Board,
+    start_x_turn: bool,
+    moves: Vec<(bool, Ply)>,
+    termination: Termination,
+) -> io::Result<GameRecord> {
+    let mut board = start.clone();
+    let mut x_turn = start_x_turn;
+    let mut plies = Vec::new();
+
+    for (black, ply) in moves {
+        if black != x_turn {
+            if board.num_moves(x_turn) != 0 {
+                return Err(invalid_data(format!(
+                    "move out of turn at ply {}",
+                    plies.len() + 1
+                )));
+            }
+            plies.push(Ply::new(None));
+            x_turn = !x_turn;
+        }
+
+        let legal = board.legal_moves(x_turn);
+        match ply.mv {
+            Some(mv) if legal & mv != 0 => board.apply_move(mv, x_turn),
+            Some(mv) => {
+                return Err(invalid_data(format!(
+                    "illegal move {} at ply {}",
+                    Board::square_name(mv),
+                    plies.len() + 1
+                )));
+            }
+            None if legal != 0 => {
+                return Err(invalid_data(format!(
+                    "pass with moves available at ply {}",
+                    plies.len() + 1
+                )));
+            }
+            None => {}
+        }
+        plies.push(ply);
+        x_turn = !x_turn;
+    }
+
+    let score = Score::with_termination(
+        board.get_x().count_ones(),
+        board.get_o().count_ones(),
+        termination,
+    );
+    Ok(GameRecord::new(start, start_x_turn, plies, score))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A record as GGS writes it, with the board in rows.
+    const GGS_RECORD: &str = concat!(
+        "(;GM[Othello]PC[GGS/os]DT[2003.12.15_13:24:03.MST]PB[saio]PW[zebra]RB[2197.72]",
+        "RW[2199.94]TI[05:00//02:00]TY[8]RE[+0.000]",
+        "BO[8 -------- -------- -------- ---O*--- ---*O--- -------- -------- -------- *]",
+        "B[d3//0.01]W[c5/-2/1.50]B[f6//0.20]W[f5/1/0.03];)"
+    );
+
+    #[test]
+    fn reads_a_ggs_record() {
+        let games = GgfGame::parse_all(GGS_RECORD).unwrap();
+        let [game] = &games[..] else {
+            panic!("expected one game");
+        };
+        assert_eq!(
+            (game.black.as_str(), game.white.as_str()),
+            ("saio", "zebra")
+        );
+        assert_eq!(game.black_rating, Some(2197.72));
+        assert_eq!(game.result, Some(0.0));
+        assert_eq!(game.record.start().to_compact(), Board::new().to_compact());
+        assert!(game.record.start_x_turn());
+
+        let moves = game.record.moves();
+        assert_eq!(moves.len(), 4);
+        assert_eq!(moves[1].mv, Board::parse_square("c5"));
+        assert_eq!(moves[1].eval, Some(-2));
+        assert_eq!(moves[1].time, Duration::from_millis(1500));
+    }
+
+    #[test]
+    fn exports_a_ggs_record_back() {
+        let game = GgfGame::parse_all(GGS_RECORD).unwrap().remove(0);
+        assert_eq!(game.to_string(), GGS_RECORD);
+    }
+
+    // The game from the GGS record, ended by the given termination instead.
+    fn ended_by(termination: Termination) -> GameRecord {
+        let record = GgfGame::parse_all(GGS_RECORD).unwrap().remove(0).record;
+        let (board, _) = record.positions().pop().unwrap();
+        let score = Score::with_termination(
+            board.get_x().count_ones(),
+            board.get_o().count_ones(),
+            termination,
+        );
+        GameRecord::new(
+            record.start().clone(),
+            record.start_x_turn(),
+            record.moves().to_vec(),
+            score,
+        )
+    }
+
+    #[test]
+    fn timeouts_and_forfeits_survive_a_round_trip() {
+        for (termination, result) in [
+            (Termination::Timeout('x'), "RE[-64.000:t]"),
+            (
+                Termination::Forfeit('o', Violation::Resigned),
+                "RE[+64.000:r]",
+            ),
+        ] {
+            let text = GgfGame::from_record(ended_by(termination), "a", "b").to_string();
+            assert!(text.contains(result), "{text}");
+            let game = GgfGame::parse_all(&text).unwrap().remove(0);
+            assert_eq!(game.record.score().termination(), termination);
+        }
+
+        // Forfeits for a broken rule are read back as resignations, GGF doesn't say which rule.
+        let forfeit = Termination::Forfeit('x', Violation::IllegalPass);
+        let text = GgfGame::from_record(ended_by(forfeit), "a", "b").to_string();
+        let game = GgfGame::parse_all(&text).unwrap().remove(0);
+        assert_eq!(
+            game.record.score().termination(),
+            Termination::Forfeit('x', Violation::Resigned)
+        );
+    }
+
+    #[test]
+    fn finished_games_keep_their_result() {
+        let game = GgfGame::parse_all(GGS_RECORD).unwrap().remove(0);
+        assert_eq!(game.record.score().termination(), Termination::Finished);
+    }
+
+    #[test]
+    fn rejects_move_times_out_of_range() {
+        for time in ["1e300", "-1", "1:1e300", "soon"] {
+            let text = GGS_RECORD.replace("B[f6//0.20]", &format!("B[f6//{time}]"));
+            assert!(GgfGame::parse_all(&text).is_err(), "{time}");
+        }
+    }
+
+    #[test]
+    fn rejects_a_board_without_64_squares() {
+        let text = GGS_RECORD.replace("-------- *]", "------- *]");
+        assert!(GgfGame::parse_all(&text).is_err());
+    }
+}
@@ -1,5 +1,19 @@
+pub mod bench;
 pub mod board;
+pub mod cli;
+pub mod clock;
 pub mod eval;
 pub mod game;
+pub mod ggf;
+pub mod observer;
+pub mod openings;
 pub mod player;
+pub mod rating;
+pub mod report;
+pub mod save;
+pub mod sprt;
+pub mod suite;
+pub mod tournament;
+pub mod transcript;
 pub mod utils;
+pub mod wthor;
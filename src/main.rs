@@ -1,64 +1,177 @@
 use core::panic;
 use othello::{
-    eval::{GoodEval, SimpleEval},
-    game::{Game, Outcome},
-    player::{Human, Negamax, Player, PlayerKind, RandomAI},
+    board::Board,
+    cli::Args,
+    clock::{Clock, TimeControl},
+    game::{Game, GameRecord, Outcome},
+    ggf::GgfGame,
+    observer::TerminalPrinter,
+    player::{Configured, PlayerSpec},
+    save::SavedGame,
+    transcript,
 };
+use std::env;
 use std::io;
+use std::process;
 
-fn run_game<P1: Player, P2: Player>(p1: P1, p2: P2) {
-    let mut game = Game::new(p1, p2);
-    let score = game.run(true);
+const USAGE: &str = "Usage: othello [--resume <save file>] [--load <ggf file> [--game <n>]]
+               [--board <board> [--side x|o]] [--moves <transcript>] [--time <control>]
+               [--export <ggf file>]
+
+--board starts from a position written as 64 characters from a8 to h1 using x, o and -.
+--moves starts the game after the given moves, like f5d6c3.
+--time plays with clocks, as base+increment like 300+5 or per move like 10/move in seconds.
+--export writes the finished game in GGF.";
+
+// Menu entries with the players for x and o, written as in PlayerSpec.
+const MODES: [(&str, &str, &str); 6] = [
+    ("player vs player", "human", "human"),
+    ("player vs random", "human", "random"),
+    ("random vs random", "random", "random"),
+    ("player vs best algorithm", "human", "negamax 10 good"),
+    ("random vs best algorithm", "random", "negamax 10 good"),
+    ("player vs easy algorithm", "random", "negamax 5 simple"),
+];
+
+fn fail(msg: &str) -> ! {
+    eprintln!("{msg}");
+    eprintln!("{USAGE}");
+    process::exit(1);
+}
+
+fn new_game(x: &PlayerSpec, o: &PlayerSpec) -> Game<Configured, Configured> {
+    let build = |spec: &PlayerSpec, symbol| {
+        spec.build(symbol)
+            .unwrap_or_else(|err| fail(&format!("Could not create {spec}: {err}")))
+    };
+    let mut game = Game::new(build(x, 'x'), build(o, 'o'));
+    game.add_observer(TerminalPrinter);
+    game
+}
+
+// Plays on from the end of the record, or from the start without one. A clock from a saved game
+// replaces the fresh one of the time control.
+fn run_game(
+    x: &PlayerSpec,
+    o: &PlayerSpec,
+    from: Option<&GameRecord>,
+    time_control: Option<TimeControl>,
+    clock: Option<Clock>,
+) -> GameRecord {
+    let mut game = new_game(x, o);
+    game.set_time_control(time_control);
+    if let Some(record) = from {
+        game.load_record(record);
+        while game.redo() {}
+    }
+    if clock.is_some() {
+        game.set_clock(clock);
+    }
+    game.run()
+}
+
+// Loads a game into a player vs player session, its moves can be stepped through with redo.
+fn replay(path: &str, index: usize) -> GameRecord {
+    let games = GgfGame::read_file(path)
+        .unwrap_or_else(|err| fail(&format!("Could not load {path}: {err}")));
+    let Some(ggf) = games.get(index) else {
+        fail(&format!("{path} has {} games", games.len()));
+    };
+    println!(
+        "{} vs {}, type redo to step through the game",
+        ggf.black, ggf.white
+    );
+
+    let mut game = new_game(&PlayerSpec::Human, &PlayerSpec::Human);
+    game.load_record(&ggf.record);
+    game.run()
+}
+
+// TerminalPrinter has already shown how the game ended.
+fn print_result(record: &GameRecord) {
+    let score = record.score();
     match score.outcome() {
         Outcome::Xwin => println!("X won"),
         Outcome::Draw => println!("Draw"),
         Outcome::OWin => println!("O won"),
     }
     println!("x - {} to o - {} tiles", score.x(), score.o());
+    println!("{}", transcript::to_transcript(record));
+}
+
+fn export(args: &Args, record: GameRecord, x: &PlayerSpec, o: &PlayerSpec) {
+    if let Some(path) = args.get("export") {
+        let game = GgfGame::from_record(record, &x.to_string(), &o.to_string());
+        match GgfGame::write_file(path, &[game]) {
+            Ok(()) => println!("Saved the game to {path}"),
+            Err(err) => eprintln!("Could not save {path}: {err}"),
+        }
+    }
 }
 
 fn main() {
+    let args = Args::parse(env::args().skip(1)).unwrap_or_else(|err| fail(&err));
+    let time_control = args
+        .get("time")
+        .map(|control| control.parse().unwrap_or_else(|err: String| fail(&err)));
+    if let Some(path) = args.get("resume") {
+        let saved = SavedGame::from_file(path)
+            .unwrap_or_else(|err| fail(&format!("Could not load {path}: {err}")));
+        // --time starts fresh clocks instead of the saved ones.
+        let clock = if time_control.is_some() {
+            None
+        } else {
+            saved.clock
+        };
+        let record = run_game(&saved.x, &saved.o, Some(&saved.record), time_control, clock);
+        print_result(&record);
+        export(&args, record, &saved.x, &saved.o);
+        return;
+    }
+    if let Some(path) = args.get("load") {
+        let game = args.value("game", 0).unwrap_or_else(|err| fail(&err));
+        let record = replay(path, game);
+        print_result(&record);
+        export(&args, record, &PlayerSpec::Human, &PlayerSpec::Human);
+        return;
+    }
+
+    let (board, x_turn) = match args.get("board") {
+        Some(board) => {
+            let side = args.get("side").unwrap_or("x");
+            Board::parse_position(&format!("{board} {side}"))
+                .unwrap_or_else(|| fail("Invalid board or side to move"))
+        }
+        None => (Board::new(), true),
+    };
+    let opening = transcript::parse_moves(args.get("moves").unwrap_or_default())
+        .and_then(|moves| transcript::replay(&board, x_turn, &moves))
+        .unwrap_or_else(|err| fail(&format!("Invalid transcript, {err}")));
+    if opening.positions().pop().unwrap().0.is_over() {
+        fail("The game is already over");
+    }
+
     println!("Choose mode:");
-    println!("1 - player vs player");
-    println!("2 - player vs random");
-    println!("3 - random vs random");
-    println!("4 - player vs best algorithm");
-    println!("5 - random vs best algorithm");
-    println!("6 - player vs easy algorithm");
+    for (i, (name, _, _)) in MODES.iter().enumerate() {
+        println!("{} - {name}", i + 1);
+    }
 
     let mut input = String::new();
     let _ = io::stdin().read_line(&mut input);
-
-    type PKSim = PlayerKind<SimpleEval>;
-    type PKBest = PlayerKind<GoodEval>;
+    let Some(&(_, x, o)) = input
+        .trim()
+        .parse::<usize>()
+        .ok()
+        .and_then(|mode| MODES.get(mode.checked_sub(1)?))
+    else {
+        panic!("Invalid option");
+    };
+    let x = PlayerSpec::parse(x).unwrap();
+    let o = PlayerSpec::parse(o).unwrap();
 
     print!("{}[2J", 27 as char); // clear terminal
-    match input.trim() {
-        "1" => run_game(PKSim::Human(Human::new('x')), PKSim::Human(Human::new('o'))),
-
-        "2" => run_game(
-            PKSim::Human(Human::new('x')),
-            PKSim::Random(RandomAI::new('o')),
-        ),
-        "3" => run_game(
-            PKSim::Random(RandomAI::new('x')),
-            PKSim::Random(RandomAI::new('o')),
-        ),
-        "4" => run_game(
-            PKSim::Human(Human::new('x')),
-            PKBest::Negamax(Negamax::new('o', 10, GoodEval {})),
-        ),
-
-        "5" => run_game(
-            PKSim::Random(RandomAI::new('x')),
-            PKBest::Negamax(Negamax::new('o', 10, GoodEval {})),
-        ),
-
-        "6" => run_game(
-            PKSim::Random(RandomAI::new('x')),
-            PKSim::Negamax(Negamax::new('o', 5, SimpleEval {})),
-        ),
-
-        _ => panic!("Invalid option"),
-    };
+    println!("Type save <file> to keep the game for later, then resume it with --resume <file>");
+    let record = run_game(&x, &o, Some(&opening), time_control, None);
+    print_result(&record);
+    export(&args, record, &x, &o);
 }
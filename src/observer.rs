@@ -0,0 +1,99 @@
+use crate::board::Board;
+use crate::game::{GameRecord, Termination, Violation};
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+// Gets told what happens during Game::run. Every callback does nothing by default,
+// so an observer only implements the events it cares about.
+pub trait GameObserver {
+    fn on_start(&mut self, _board: &Board, _x_turn: bool) {}
+
+    // Before each turn, with the remaining time of the side to move in games with a clock.
+    fn on_turn(&mut self, _board: &Board, _x_turn: bool, _remaining: Option<Duration>) {}
+
+    // The board after the move, flips are the discs it turned over.
+    fn on_move(&mut self, _board: &Board, _mv: u64, _flips: u64, _x_turn: bool) {}
+
+    fn on_pass(&mut self, _board: &Board, _x_turn: bool) {}
+
+    // The side to move broke the rules, it forfeits and on_end follows.
+    fn on_illegal_move(&mut self, _board: &Board, _violation: Violation, _x_turn: bool) {}
+
+    // The position after moves were taken back or replayed.
+    fn on_take_back(&mut self, _board: &Board, _x_turn: bool) {}
+
+    // Notes for the players, like the result of saving the game.
+    fn on_message(&mut self, _message: &str) {}
+
+    fn on_end(&mut self, _board: &Board, _record: &GameRecord) {}
+}
+
+// Lets the caller keep a handle on an observer, to read what it collected after the game.
+impl<O: GameObserver> GameObserver for Rc<RefCell<O>> {
+    fn on_start(&mut self, board: &Board, x_turn: bool) {
+        self.borrow_mut().on_start(board, x_turn)
+    }
+
+    fn on_turn(&mut self, board: &Board, x_turn: bool, remaining: Option<Duration>) {
+        self.borrow_mut().on_turn(board, x_turn, remaining)
+    }
+
+    fn on_move(&mut self, board: &Board, mv: u64, flips: u64, x_turn: bool) {
+        self.borrow_mut().on_move(board, mv, flips, x_turn)
+    }
+
+    fn on_pass(&mut self, board: &Board, x_turn: bool) {
+        self.borrow_mut().on_pass(board, x_turn)
+    }
+
+    fn on_illegal_move(&mut self, board: &Board, violation: Violation, x_turn: bool) {
+        self.borrow_mut().on_illegal_move(board, violation, x_turn)
+    }
+
+    fn on_take_back(&mut self, board: &Board, x_turn: bool) {
+        self.borrow_mut().on_take_back(board, x_turn)
+    }
+
+    fn on_message(&mut self, message: &str) {
+        self.borrow_mut().on_message(message)
+    }
+
+    fn on_end(&mut self, board: &Board, record: &GameRecord) {
+        self.borrow_mut().on_end(board, record)
+    }
+}
+
+// Shows the game in the terminal, the way the interactive game always has.
+pub struct TerminalPrinter;
+
+impl GameObserver for TerminalPrinter {
+    fn on_turn(&mut self, board: &Board, x_turn: bool, remaining: Option<Duration>) {
+        println!();
+        board.print(x_turn);
+        if x_turn {
+            println!("X to move");
+        } else {
+            println!("O to move");
+        }
+        if let Some(remaining) = remaining {
+            let seconds = remaining.as_secs_f64();
+            println!("{}:{:04.1} left", (seconds / 60.0) as u64, seconds % 60.0);
+        }
+    }
+
+    fn on_pass(&mut self, _board: &Board, _x_turn: bool) {
+        println!("No moves available - skipping turn")
+    }
+
+    fn on_message(&mut self, message: &str) {
+        println!("{message}");
+    }
+
+    fn on_end(&mut self, board: &Board, record: &GameRecord) {
+        match record.score().termination() {
+            Termination::Finished => board.print(record.positions().pop().unwrap().1),
+            termination => println!("{termination}"),
+        }
+    }
+}
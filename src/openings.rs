@@ -0,0 +1,76 @@
+use crate::board::Board;
+use crate::transcript;
+use crate::utils::invalid_data;
+use rand::Rng;
+use rand::SeedableRng;
+use rand::rngs::SmallRng;
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// Opening suites are files of short games from the usual start, one transcript per line like
+// f5d6c3d3c4f4c5b3, in the style of the XOT 8-move set. Matches start from the positions they
+// reach, so deterministic engines still play different games.
+
+// Reads a suite as the positions its openings reach, with the side to move.
+// Empty lines and lines starting with # are skipped.
+pub fn read_openings(path: impl AsRef<Path>) -> io::Result<Vec<(Board, bool)>> {
+    fs::read_to_string(path)?
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let record =
+                transcript::parse(line).map_err(|err| invalid_data(format!("{line}: {err}")))?;
+            Ok(record.positions().pop().unwrap())
+        })
+        .collect()
+}
+
+// Plays random openings of the given number of moves and keeps the distinct ones whose position
+// is balanced: eval gives its score for the side to move, or None to reject the position, and
+// scores beyond max_eval either way are rejected. Gives up after 1000 tries per opening asked for,
+// count * 1000 in all.
+pub fn generate(
+    count: usize,
+    moves: usize,
+    seed: u64,
+    max_eval: i32,
+    mut eval: impl FnMut(&Board, bool) -> Option<i32>,
+) -> Vec<String> {
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let mut seen = HashSet::new();
+    let mut openings = Vec::new();
+
+    for _ in 0..count * 1000 {
+        if openings.len() == count {
+            break;
+        }
+        let mut board = Board::new();
+        let mut x_turn = true;
+        let mut played = Vec::new();
+        while played.len() < moves && !board.is_over() {
+            let num_moves = board.num_moves(x_turn);
+            if num_moves > 0 {
+                let num = rng.random_range(0..num_moves) as usize;
+                let mv = board.moves_iter(x_turn).nth(num).unwrap();
+                board.apply_move(mv, x_turn);
+                played.push(mv);
+            }
+            x_turn = !x_turn;
+        }
+
+        let text: String = played.iter().map(|&mv| Board::square_name(mv)).collect();
+        if board.is_over() || seen.contains(&text) {
+            continue;
+        }
+        if let Some(score) = eval(&board, x_turn)
+            && score.abs() <= max_eval
+        {
+            seen.insert(text.clone());
+            openings.push(text);
+        }
+    }
+    openings
+}
@@ -2,6 +2,7 @@ use crate::board::Board;
 use crate::board::MovesIter;
 use crate::eval::Evaluator;
 use crate::player::Player;
+use std::cell::Cell;
 use std::cmp::max;
 use std::cmp::min;
 
@@ -9,6 +10,9 @@ pub struct AlphaBeta<E: Evaluator> {
     symbol: char,
     depth: u8,
     eval: E,
+    last_eval: Option<i32>,
+    // Positions visited by the last search.
+    nodes: Cell<u64>,
 }
 
 impl<E: Evaluator> AlphaBeta<E> {
@@ -17,6 +21,8 @@ impl<E: Evaluator> AlphaBeta<E> {
             symbol,
             depth,
             eval,
+            last_eval: None,
+            nodes: Cell::new(0),
         }
     }
 
@@ -28,8 +34,9 @@ impl<E: Evaluator> AlphaBeta<E> {
         mut alpha: i32,
         mut beta: i32,
     ) -> (i32, u64) {
+        self.nodes.set(self.nodes.get() + 1);
         if depth == 0 || board.is_over() {
-            return (self.eval.eval(board), 0);
+            return (self.eval.eval(&board), 0);
         }
 
         let moves = board.legal_moves(x_turn);
@@ -79,16 +86,32 @@ impl<E: Evaluator> Player for AlphaBeta<E> {
     }
 
     fn get_move(&mut self, board: Board) -> Option<u64> {
+        self.nodes.set(0);
         if board.num_moves(self.get_symbol() == 'x') == 0 {
+            self.last_eval = None;
             return None;
         }
-        let (_, mv) = self.search(
+        let (eval, mv) = self.search(
             board,
             self.depth,
             self.get_symbol() == 'x',
             i32::MIN,
             i32::MAX,
         );
+        // The search scores for x, the mover wants its own point of view.
+        self.last_eval = Some(if self.get_symbol() == 'x' {
+            eval
+        } else {
+            -eval
+        });
         Some(mv)
     }
+
+    fn last_eval(&self) -> Option<i32> {
+        self.last_eval
+    }
+
+    fn last_nodes(&self) -> Option<u64> {
+        self.last_eval.map(|_| self.nodes.get())
+    }
 }
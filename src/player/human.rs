@@ -1,5 +1,5 @@
 use crate::board::Board;
-use crate::player::Player;
+use crate::player::{Action, Player};
 use std::io;
 
 pub struct Human {
@@ -12,14 +12,7 @@ impl Human {
     }
 
     pub fn parse_input(input: &str) -> Option<u64> {
-        let mut chars = input.chars();
-        let file = chars.next()?;
-        let rank = chars.next()?;
-        if chars.next().is_some() {
-            return None;
-        }
-        let mv = Board::get_move(b'8' - rank as u8, file as u8 - b'a');
-        Some(mv)
+        Board::parse_square(input)
     }
 }
 
@@ -29,19 +22,39 @@ impl Player for Human {
     }
 
     fn get_move(&mut self, board: Board) -> Option<u64> {
+        loop {
+            match self.get_action(board.clone()) {
+                Action::Move(mv) => return Some(mv),
+                Action::Pass => return None,
+                Action::Undo | Action::Redo => println!("Can't take moves back here."),
+                Action::Save(_) => println!("Can't save here."),
+            }
+        }
+    }
+
+    fn get_action(&mut self, board: Board) -> Action {
         if board.num_moves(self.get_symbol() == 'x') == 0 {
-            return None;
+            return Action::Pass;
         }
         let mut input = String::new();
-        println!("Enter move");
+        println!("Enter move (or undo / redo / save <file>)");
         loop {
             input.clear();
             let _ = io::stdin().read_line(&mut input);
 
-            if let Some(mv) = Human::parse_input(input.trim())
-                && board.is_legal(mv, self.get_symbol() == 'x')
-            {
-                return Some(mv);
+            match input.trim() {
+                "undo" => return Action::Undo,
+                "redo" => return Action::Redo,
+                input if input.starts_with("save ") => {
+                    return Action::Save(input["save ".len()..].trim().to_string());
+                }
+                input => {
+                    if let Some(mv) = Human::parse_input(input)
+                        && board.is_legal(mv, self.get_symbol() == 'x')
+                    {
+                        return Action::Move(mv);
+                    }
+                }
             }
             println!("Illegal move.");
         }
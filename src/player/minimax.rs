@@ -1,11 +1,15 @@
 use crate::board::{Board, MovesIter};
 use crate::eval::Evaluator;
 use crate::player::Player;
+use std::cell::Cell;
 
 pub struct Minimax<E: Evaluator> {
     symbol: char,
     depth: u8,
     eval: E,
+    last_eval: Option<i32>,
+    // Positions visited by the last search.
+    nodes: Cell<u64>,
 }
 
 impl<E: Evaluator> Minimax<E> {
@@ -14,12 +18,15 @@ impl<E: Evaluator> Minimax<E> {
             symbol,
             depth,
             eval,
+            last_eval: None,
+            nodes: Cell::new(0),
         }
     }
 
     fn search(&self, board: Board, depth: u8, x_turn: bool) -> (i32, u64) {
+        self.nodes.set(self.nodes.get() + 1);
         if depth == 0 || board.is_over() {
-            return (self.eval.eval(board), 0);
+            return (self.eval.eval(&board), 0);
         }
 
         let moves = board.legal_moves(x_turn);
@@ -61,10 +68,26 @@ impl<E: Evaluator> Player for Minimax<E> {
     }
 
     fn get_move(&mut self, board: Board) -> Option<u64> {
+        self.nodes.set(0);
         if board.num_moves(self.get_symbol() == 'x') == 0 {
+            self.last_eval = None;
             return None;
         }
-        let (_, mv) = self.search(board, self.depth, self.get_symbol() == 'x');
+        let (eval, mv) = self.search(board, self.depth, self.get_symbol() == 'x');
+        // The search scores for x, the mover wants its own point of view.
+        self.last_eval = Some(if self.get_symbol() == 'x' {
+            eval
+        } else {
+            -eval
+        });
         Some(mv)
     }
+
+    fn last_eval(&self) -> Option<i32> {
+        self.last_eval
+    }
+
+    fn last_nodes(&self) -> Option<u64> {
+        self.last_eval.map(|_| self.nodes.get())
+    }
 }
@@ -1,5 +1,7 @@
+use crate::clock::TimeControl;
 use crate::player::{Player, RandomAI};
 use rand::{Rng, SeedableRng, rngs::SmallRng};
+use std::time::Duration;
 
 // Since the best algorithm wins against random moves 100% of the time
 // there needs to be something non deterministic to benchmark against
@@ -9,6 +11,7 @@ pub struct Mixed<P1: Player> {
     symbol: char,
     rand_chance: f64,
     rng: SmallRng,
+    random_move: bool,
 }
 
 impl<P1: Player> Mixed<P1> {
@@ -19,6 +22,7 @@ impl<P1: Player> Mixed<P1> {
             symbol,
             rand_chance,
             rng: SmallRng::seed_from_u64(seed),
+            random_move: false,
         }
     }
 }
@@ -30,10 +34,31 @@ impl<P1: Player> Player for Mixed<P1> {
 
     fn get_move(&mut self, board: crate::board::Board) -> Option<u64> {
         let random: f64 = self.rng.random();
-        if random < self.rand_chance {
+        self.random_move = random < self.rand_chance;
+        if self.random_move {
             self.p2.get_move(board)
         } else {
             self.p1.get_move(board)
         }
     }
+
+    fn set_clock(&mut self, control: TimeControl, remaining: Duration) {
+        self.p1.set_clock(control, remaining)
+    }
+
+    fn last_eval(&self) -> Option<i32> {
+        if self.random_move {
+            None
+        } else {
+            self.p1.last_eval()
+        }
+    }
+
+    fn last_nodes(&self) -> Option<u64> {
+        if self.random_move {
+            None
+        } else {
+            self.p1.last_nodes()
+        }
+    }
 }
@@ -4,9 +4,12 @@ pub mod minimax;
 pub mod mixed;
 pub mod negamax;
 pub mod random;
+pub mod spec;
 
 use crate::board::Board;
+use crate::clock::TimeControl;
 use crate::eval::Evaluator;
+use std::time::Duration;
 
 pub use alphabeta::AlphaBeta;
 pub use human::Human;
@@ -14,10 +17,75 @@ pub use minimax::Minimax;
 pub use mixed::Mixed;
 pub use negamax::Negamax;
 pub use random::RandomAI;
+pub use spec::{Configured, PlayerSpec};
+
+// What a player wants to do on its turn. Only interactive players take moves back or save.
+pub enum Action {
+    Move(u64),
+    Pass,
+    Undo,
+    Redo,
+    Save(String), // path of the save file
+}
 
 pub trait Player {
     fn get_move(&mut self, board: Board) -> Option<u64>;
     fn get_symbol(&self) -> char;
+
+    fn get_action(&mut self, board: Board) -> Action {
+        match self.get_move(board) {
+            Some(mv) => Action::Move(mv),
+            None => Action::Pass,
+        }
+    }
+
+    // Score of the last move from the mover's point of view, for players that search.
+    fn last_eval(&self) -> Option<i32> {
+        None
+    }
+
+    // Positions searched for the last move, for players that search.
+    fn last_nodes(&self) -> Option<u64> {
+        None
+    }
+
+    // How to build this player again, for players that can be saved with a game.
+    fn spec(&self) -> Option<PlayerSpec> {
+        None
+    }
+
+    // Called before each move of a game with a clock, players that search can budget with it.
+    fn set_clock(&mut self, _control: TimeControl, _remaining: Duration) {}
+}
+
+impl<P: Player + ?Sized> Player for Box<P> {
+    fn get_symbol(&self) -> char {
+        (**self).get_symbol()
+    }
+
+    fn get_move(&mut self, board: Board) -> Option<u64> {
+        (**self).get_move(board)
+    }
+
+    fn get_action(&mut self, board: Board) -> Action {
+        (**self).get_action(board)
+    }
+
+    fn last_eval(&self) -> Option<i32> {
+        (**self).last_eval()
+    }
+
+    fn last_nodes(&self) -> Option<u64> {
+        (**self).last_nodes()
+    }
+
+    fn spec(&self) -> Option<PlayerSpec> {
+        (**self).spec()
+    }
+
+    fn set_clock(&mut self, control: TimeControl, remaining: Duration) {
+        (**self).set_clock(control, remaining)
+    }
 }
 
 pub enum PlayerKind<E: Evaluator> {
@@ -48,4 +116,44 @@ impl<E: Evaluator> Player for PlayerKind<E> {
             PlayerKind::Negamax(p) => p.get_move(board),
         }
     }
+
+    fn get_action(&mut self, board: Board) -> Action {
+        match self {
+            PlayerKind::Human(p) => p.get_action(board),
+            PlayerKind::Random(p) => p.get_action(board),
+            PlayerKind::Minimax(p) => p.get_action(board),
+            PlayerKind::AlphaBeta(p) => p.get_action(board),
+            PlayerKind::Negamax(p) => p.get_action(board),
+        }
+    }
+
+    fn last_eval(&self) -> Option<i32> {
+        match self {
+            PlayerKind::Human(p) => p.last_eval(),
+            PlayerKind::Random(p) => p.last_eval(),
+            PlayerKind::Minimax(p) => p.last_eval(),
+            PlayerKind::AlphaBeta(p) => p.last_eval(),
+            PlayerKind::Negamax(p) => p.last_eval(),
+        }
+    }
+
+    fn last_nodes(&self) -> Option<u64> {
+        match self {
+            PlayerKind::Human(p) => p.last_nodes(),
+            PlayerKind::Random(p) => p.last_nodes(),
+            PlayerKind::Minimax(p) => p.last_nodes(),
+            PlayerKind::AlphaBeta(p) => p.last_nodes(),
+            PlayerKind::Negamax(p) => p.last_nodes(),
+        }
+    }
+
+    fn set_clock(&mut self, control: TimeControl, remaining: Duration) {
+        match self {
+            PlayerKind::Human(p) => p.set_clock(control, remaining),
+            PlayerKind::Random(p) => p.set_clock(control, remaining),
+            PlayerKind::Minimax(p) => p.set_clock(control, remaining),
+            PlayerKind::AlphaBeta(p) => p.set_clock(control, remaining),
+            PlayerKind::Negamax(p) => p.set_clock(control, remaining),
+        }
+    }
 }
@@ -1,14 +1,24 @@
 use crate::board::{Board, MovesIter};
-use crate::eval::Evaluator;
+use crate::clock::TimeControl;
+use crate::eval::{Evaluator, SideRelative};
 use crate::player::Player;
+use std::cell::Cell;
 use std::cmp::max;
+use std::time::{Duration, Instant};
 
 // This is the best algorithm here so I will do methods that are implemented in board by hand to reuse the same values.
 // This doesn't give that much performance so I will leave other files as they for readability.
 pub struct Negamax<E: Evaluator> {
     symbol: char,
     depth: u8,
-    eval: E,
+    eval: SideRelative<E>,
+    last_eval: Option<i32>,
+    // Positions visited by the last search.
+    nodes: Cell<u64>,
+    // With a clock the depth is only a limit, the search deepens until the budget is used up.
+    clock: Option<(TimeControl, Duration)>,
+    deadline: Option<Instant>,
+    stopped: Cell<bool>,
 }
 
 impl<E: Evaluator> Negamax<E> {
@@ -16,26 +26,58 @@ impl<E: Evaluator> Negamax<E> {
         Self {
             symbol,
             depth,
-            eval,
+            eval: SideRelative::new(eval),
+            last_eval: None,
+            nodes: Cell::new(0),
+            clock: None,
+            deadline: None,
+            stopped: Cell::new(false),
         }
     }
 
+    // Searches one ply deeper at a time and keeps the last search that finished in time.
+    // The first search always finishes so there is a move to play.
+    fn deepen(&mut self, board: Board, x_turn: bool, budget: Duration) -> (i32, u64) {
+        let deadline = Instant::now() + budget;
+        let mut best = self.search(board.clone(), 1, x_turn, i32::MIN + 1, i32::MAX - 1);
+        for depth in 2..=self.depth {
+            if Instant::now() >= deadline {
+                break;
+            }
+            self.deadline = Some(deadline);
+            let result = self.search(board.clone(), depth, x_turn, i32::MIN + 1, i32::MAX - 1);
+            self.deadline = None;
+            if self.stopped.replace(false) {
+                break;
+            }
+            best = result;
+        }
+        best
+    }
+
     fn search(
         &self,
         board: Board,
         depth: u8,
         x_turn: bool,
-        color: i8,
         mut alpha: i32,
         beta: i32,
     ) -> (i32, u64) {
+        self.nodes.set(self.nodes.get() + 1);
         if depth == 0 || board.is_over() {
-            return (color as i32 * self.eval.eval(board), 0);
+            return (self.eval.eval_for(&board, x_turn), 0);
+        }
+
+        if let Some(deadline) = self.deadline
+            && (self.stopped.get() || Instant::now() >= deadline)
+        {
+            self.stopped.set(true);
+            return (0, 0);
         }
 
         let moves = board.legal_moves(x_turn);
         if moves == 0 {
-            let (eval, mv) = self.search(board, depth - 1, !x_turn, -color, -beta, -alpha);
+            let (eval, mv) = self.search(board, depth - 1, !x_turn, -beta, -alpha);
             return (-eval, mv);
         };
 
@@ -45,7 +87,7 @@ impl<E: Evaluator> Negamax<E> {
         for mv in MovesIter::new(moves) {
             let mut temp_board = board.clone();
             temp_board.apply_move(mv, x_turn);
-            let (eval, _) = self.search(temp_board, depth - 1, !x_turn, -color, -beta, -alpha);
+            let (eval, _) = self.search(temp_board, depth - 1, !x_turn, -beta, -alpha);
             if -eval > value {
                 value = -eval;
                 best_move = mv;
@@ -66,17 +108,32 @@ impl<E: Evaluator> Player for Negamax<E> {
     }
 
     fn get_move(&mut self, board: Board) -> Option<u64> {
+        self.nodes.set(0);
         if board.num_moves(self.get_symbol() == 'x') == 0 {
+            self.last_eval = None;
             return None;
         }
-        let (_, mv) = self.search(
-            board,
-            self.depth,
-            self.get_symbol() == 'x',
-            if self.get_symbol() == 'x' { 1 } else { -1 },
-            i32::MIN + 1,
-            i32::MAX - 1,
-        );
+        let x_turn = self.get_symbol() == 'x';
+        let empties = (!(board.get_x() | board.get_o())).count_ones();
+        let (eval, mv) = match self.clock.take() {
+            Some((control, remaining)) => {
+                self.deepen(board, x_turn, control.budget(remaining, empties))
+            }
+            None => self.search(board, self.depth, x_turn, i32::MIN + 1, i32::MAX - 1),
+        };
+        self.last_eval = Some(eval);
         Some(mv)
     }
+
+    fn last_eval(&self) -> Option<i32> {
+        self.last_eval
+    }
+
+    fn last_nodes(&self) -> Option<u64> {
+        self.last_eval.map(|_| self.nodes.get())
+    }
+
+    fn set_clock(&mut self, control: TimeControl, remaining: Duration) {
+        self.clock = Some((control, remaining));
+    }
 }
@@ -0,0 +1,162 @@
+use crate::board::Board;
+use crate::clock::TimeControl;
+use crate::eval::EvalSpec;
+use crate::player::{Action, AlphaBeta, Human, Minimax, Mixed, Negamax, Player, RandomAI};
+use std::fmt;
+use std::io;
+use std::time::Duration;
+
+// A player written as text, like human, random or negamax 10 good: the algorithm,
+// then for searching players the depth and the evaluator. mixed 0.2 negamax 8 simple plays
+// a random move instead of the other player's in a fifth of its moves.
+#[derive(Clone, PartialEq, Debug)]
+pub enum PlayerSpec {
+    Human,
+    Random,
+    Minimax {
+        depth: u8,
+        eval: EvalSpec,
+    },
+    AlphaBeta {
+        depth: u8,
+        eval: EvalSpec,
+    },
+    Negamax {
+        depth: u8,
+        eval: EvalSpec,
+    },
+    Mixed {
+        chance: f64,
+        player: Box<PlayerSpec>,
+    },
+}
+
+impl PlayerSpec {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+        let (kind, rest) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+        let search = |rest: &str| {
+            let rest = rest.trim();
+            let (depth, eval) = rest
+                .split_once(char::is_whitespace)
+                .unwrap_or((rest, "good"));
+            let depth = depth
+                .parse()
+                .map_err(|_| format!("invalid depth in player {text}"))?;
+            // A search needs to look at least one move ahead to pick one.
+            if depth == 0 {
+                return Err(format!("depth must be at least 1 in player {text}"));
+            }
+            Ok::<_, String>((depth, EvalSpec::parse(eval)))
+        };
+        match kind {
+            "human" => Ok(PlayerSpec::Human),
+            "random" => Ok(PlayerSpec::Random),
+            "minimax" => search(rest).map(|(depth, eval)| PlayerSpec::Minimax { depth, eval }),
+            "alphabeta" => search(rest).map(|(depth, eval)| PlayerSpec::AlphaBeta { depth, eval }),
+            "negamax" => search(rest).map(|(depth, eval)| PlayerSpec::Negamax { depth, eval }),
+            "mixed" => {
+                let (chance, player) = rest
+                    .trim()
+                    .split_once(char::is_whitespace)
+                    .unwrap_or((rest, ""));
+                let chance = chance
+                    .parse()
+                    .ok()
+                    .filter(|chance| (0.0..=1.0).contains(chance))
+                    .ok_or_else(|| format!("invalid random chance in player {text}"))?;
+                Ok(PlayerSpec::Mixed {
+                    chance,
+                    player: Box::new(PlayerSpec::parse(player)?),
+                })
+            }
+            _ => Err(format!("unknown player {text}")),
+        }
+    }
+
+    pub fn build(&self, symbol: char) -> io::Result<Configured> {
+        self.build_with(symbol, None)
+    }
+
+    // Same as build with the random players seeded, so games can be played again.
+    pub fn build_seeded(&self, symbol: char, seed: u64) -> io::Result<Configured> {
+        self.build_with(symbol, Some(seed))
+    }
+
+    fn build_with(&self, symbol: char, seed: Option<u64>) -> io::Result<Configured> {
+        let player: Box<dyn Player> = match self {
+            PlayerSpec::Human => Box::new(Human::new(symbol)),
+            PlayerSpec::Random => match seed {
+                Some(seed) => Box::new(RandomAI::with_seed(symbol, seed)),
+                None => Box::new(RandomAI::new(symbol)),
+            },
+            PlayerSpec::Minimax { depth, eval } => {
+                Box::new(Minimax::new(symbol, *depth, eval.build()?))
+            }
+            PlayerSpec::AlphaBeta { depth, eval } => {
+                Box::new(AlphaBeta::new(symbol, *depth, eval.build()?))
+            }
+            PlayerSpec::Negamax { depth, eval } => {
+                Box::new(Negamax::new(symbol, *depth, eval.build()?))
+            }
+            PlayerSpec::Mixed { chance, player } => {
+                let seed = seed.unwrap_or_else(rand::random);
+                let player = player.build_with(symbol, Some(seed))?;
+                Box::new(Mixed::new(player, symbol, seed, *chance))
+            }
+        };
+        Ok(Configured {
+            spec: self.clone(),
+            player,
+        })
+    }
+}
+
+impl fmt::Display for PlayerSpec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PlayerSpec::Human => write!(f, "human"),
+            PlayerSpec::Random => write!(f, "random"),
+            PlayerSpec::Minimax { depth, eval } => write!(f, "minimax {depth} {eval}"),
+            PlayerSpec::AlphaBeta { depth, eval } => write!(f, "alphabeta {depth} {eval}"),
+            PlayerSpec::Negamax { depth, eval } => write!(f, "negamax {depth} {eval}"),
+            PlayerSpec::Mixed { chance, player } => write!(f, "mixed {chance} {player}"),
+        }
+    }
+}
+
+// A player built from a spec, which it keeps so a game can be saved with its players.
+pub struct Configured {
+    spec: PlayerSpec,
+    player: Box<dyn Player>,
+}
+
+impl Player for Configured {
+    fn get_symbol(&self) -> char {
+        self.player.get_symbol()
+    }
+
+    fn get_move(&mut self, board: Board) -> Option<u64> {
+        self.player.get_move(board)
+    }
+
+    fn get_action(&mut self, board: Board) -> Action {
+        self.player.get_action(board)
+    }
+
+    fn last_eval(&self) -> Option<i32> {
+        self.player.last_eval()
+    }
+
+    fn last_nodes(&self) -> Option<u64> {
+        self.player.last_nodes()
+    }
+
+    fn spec(&self) -> Option<PlayerSpec> {
+        Some(self.spec.clone())
+    }
+
+    fn set_clock(&mut self, control: TimeControl, remaining: Duration) {
+        self.player.set_clock(control, remaining)
+    }
+}
@@ -0,0 +1,213 @@
+use std::fmt;
+
+// Virtual draws each player gets against its opponents when rating a pool, as BayesElo does,
+// so a player that won or lost every game still gets a finite rating.
+const PRIOR_DRAWS: f64 = 2.0;
+
+// Elo difference of one player over another from their games, with its 95% confidence
+// interval and the likelihood of superiority, the chance the first player is the stronger one.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct EloEstimate {
+    pub elo: f64,
+    pub lower: f64,
+    pub upper: f64,
+    pub los: f64,
+}
+
+impl EloEstimate {
+    pub fn new(wins: u32, draws: u32, losses: u32) -> Self {
+        let games = (wins + draws + losses) as f64;
+        if games == 0.0 {
+            return Self {
+                elo: 0.0,
+                lower: f64::NEG_INFINITY,
+                upper: f64::INFINITY,
+                los: 0.5,
+            };
+        }
+        let (w, d, l) = (
+            wins as f64 / games,
+            draws as f64 / games,
+            losses as f64 / games,
+        );
+        let score = w + d / 2.0;
+        let variance = w * (1.0 - score).powi(2) + d * (0.5 - score).powi(2) + l * score.powi(2);
+        let margin = 1.96 * (variance / games).sqrt();
+
+        // Draws say nothing about who is stronger.
+        let decisive = (wins + losses) as f64;
+        let los = if decisive == 0.0 {
+            0.5
+        } else {
+            0.5 * (1.0 + erf((wins as f64 - losses as f64) / (2.0 * decisive).sqrt()))
+        };
+        Self {
+            elo: elo_from_score(score),
+            lower: elo_from_score(score - margin),
+            upper: elo_from_score(score + margin),
+            los,
+        }
+    }
+}
+
+impl fmt::Display for EloEstimate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // All wins or all losses leave no interval around an infinite difference.
+        let margin = if self.elo.is_finite() {
+            (self.upper - self.lower) / 2.0
+        } else {
+            f64::INFINITY
+        };
+        write!(
+            f,
+            "{} ±{} LOS {:.1}%",
+            format_elo(self.elo),
+            format_elo(margin).trim_start_matches('+'),
+            self.los * 100.0
+        )
+    }
+}
+
+// Elo difference that gives the expected score, between 0 and 1.
+pub fn elo_from_score(score: f64) -> f64 {
+    if score <= 0.0 {
+        f64::NEG_INFINITY
+    } else if score >= 1.0 {
+        f64::INFINITY
+    } else {
+        -400.0 * (1.0 / score - 1.0).log10()
+    }
+}
+
+pub fn format_elo(elo: f64) -> String {
+    if elo.is_infinite() {
+        if elo > 0.0 { "+inf" } else { "-inf" }.to_string()
+    } else {
+        // Adding 0 turns -0 into 0, which prints without the minus sign.
+        format!("{:+.0}", elo.round() + 0.0)
+    }
+}
+
+// Ratings for a pool of players from games between them, given as the two players and the first
+// one's score: 1 for a win, 0.5 for a draw and 0 for a loss. Fits a Bradley-Terry model by
+// minorization-maximization, with the prior draws of BayesElo. Ratings average 0.
+pub fn pool_ratings(players: usize, games: &[(usize, usize, f64)]) -> Vec<f64> {
+    let mut score = vec![0.0; players];
+    let mut meetings = vec![vec![0.0; players]; players];
+    for &(a, b, result) in games {
+        score[a] += result;
+        score[b] += 1.0 - result;
+        meetings[a][b] += 1.0;
+        meetings[b][a] += 1.0;
+    }
+    for a in 0..players {
+        let opponents: Vec<usize> = (0..players).filter(|&b| meetings[a][b] > 0.0).collect();
+        for &b in &opponents {
+            let draws = PRIOR_DRAWS / opponents.len() as f64;
+            score[a] += draws / 2.0;
+            score[b] += draws / 2.0;
+            meetings[a][b] += draws;
+            meetings[b][a] += draws;
+        }
+    }
+
+    let mut strength = vec![1.0; players];
+    for _ in 0..10_000 {
+        let mut change: f64 = 0.0;
+        for a in 0..players {
+            let denominator: f64 = (0..players)
+                .filter(|&b| meetings[a][b] > 0.0)
+                .map(|b| meetings[a][b] / (strength[a] + strength[b]))
+                .sum();
+            if denominator == 0.0 {
+                continue;
+            }
+            let updated = score[a] / denominator;
+            change = change.max((updated / strength[a]).ln().abs());
+            strength[a] = updated;
+        }
+        if change < 1e-9 {
+            break;
+        }
+    }
+
+    let ratings: Vec<f64> = strength.iter().map(|s| 400.0 * s.log10()).collect();
+    let mean = ratings.iter().sum::<f64>() / players.max(1) as f64;
+    ratings.iter().map(|rating| rating - mean).collect()
+}
+
+// Abramowitz and Stegun 7.1.26, accurate to about 1e-7.
+fn erf(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.327_591_1 * x.abs());
+    let poly = t
+        * (0.254_829_592
+            + t * (-0.284_496_736
+                + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    let result = 1.0 - poly * (-x * x).exp();
+    if x < 0.0 { -result } else { result }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64, tolerance: f64) -> bool {
+        (a - b).abs() < tolerance
+    }
+
+    #[test]
+    fn elo_from_known_scores() {
+        assert_eq!(elo_from_score(0.5), 0.0);
+        assert!(close(elo_from_score(0.75), 190.85, 0.01));
+        assert!(close(elo_from_score(0.25), -190.85, 0.01));
+        assert_eq!(elo_from_score(0.0), f64::NEG_INFINITY);
+        assert_eq!(elo_from_score(1.0), f64::INFINITY);
+    }
+
+    #[test]
+    fn estimates_are_symmetric() {
+        let (ahead, behind) = (EloEstimate::new(60, 10, 30), EloEstimate::new(30, 10, 60));
+        assert!(close(ahead.elo, -behind.elo, 1e-9));
+        assert!(close(ahead.lower, -behind.upper, 1e-9));
+        assert!(close(ahead.los, 1.0 - behind.los, 1e-6));
+        assert!(ahead.lower < ahead.elo && ahead.elo < ahead.upper);
+    }
+
+    #[test]
+    fn known_estimate() {
+        // 60% from 100 decisive games, with a standard error of 0.049.
+        let estimate = EloEstimate::new(60, 0, 40);
+        assert!(close(estimate.elo, 70.44, 0.01));
+        assert!(close(estimate.los, 0.97725, 1e-4));
+        assert_eq!(estimate.to_string(), "+70 ±71 LOS 97.7%");
+    }
+
+    #[test]
+    fn draws_say_nothing_about_superiority() {
+        let estimate = EloEstimate::new(0, 20, 0);
+        assert_eq!((estimate.elo, estimate.los), (0.0, 0.5));
+        assert_eq!(EloEstimate::new(0, 0, 0).los, 0.5);
+    }
+
+    #[test]
+    fn formats_elo() {
+        assert_eq!(format_elo(-0.4), "+0");
+        assert_eq!(format_elo(12.6), "+13");
+        assert_eq!(format_elo(-190.85), "-191");
+        assert_eq!(format_elo(f64::NEG_INFINITY), "-inf");
+    }
+
+    #[test]
+    fn pool_ratings_of_even_and_uneven_results() {
+        let even = pool_ratings(3, &[(0, 1, 1.0), (1, 0, 1.0), (1, 2, 0.5), (2, 0, 0.5)]);
+        for rating in even {
+            assert!(close(rating, 0.0, 1e-6));
+        }
+
+        // 3 out of 4, plus 2 prior draws from each player, is 5 out of 8 for the winner.
+        let uneven = pool_ratings(2, &[(0, 1, 1.0), (0, 1, 1.0), (0, 1, 1.0), (1, 0, 1.0)]);
+        let gap = 400.0 * (5.0f64 / 3.0).log10();
+        assert!(close(uneven[0], gap / 2.0, 1e-6));
+        assert!(close(uneven[1], -gap / 2.0, 1e-6));
+    }
+}
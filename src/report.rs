@@ -0,0 +1,338 @@
+use crate::bench::Stat;
+use crate::game::{GameRecord, Outcome, Termination};
+use crate::transcript;
+use crate::utils::invalid_data;
+use std::env;
+use std::fmt::{self, Write};
+use std::fs;
+use std::io;
+use std::thread;
+
+// The machine a benchmark ran on, so runs from different machines aren't compared by mistake.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Fingerprint {
+    pub cpu: String,
+    pub threads: usize,
+    pub os: String,
+    pub arch: String,
+    pub version: String,
+}
+
+impl Fingerprint {
+    pub fn current() -> Self {
+        // Only Linux says which CPU it is without extra dependencies.
+        let cpu = fs::read_to_string("/proc/cpuinfo")
+            .ok()
+            .and_then(|info| {
+                info.lines()
+                    .find(|line| line.starts_with("model name"))
+                    .and_then(|line| line.split_once(':'))
+                    .map(|(_, name)| name.trim().to_string())
+            })
+            .unwrap_or_else(|| "unknown".to_string());
+        Self {
+            cpu,
+            threads: thread::available_parallelism().map_or(1, |threads| threads.get()),
+            os: env::consts::OS.to_string(),
+            arch: env::consts::ARCH.to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Fingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}, {} threads, {}/{}, othello {}",
+            self.cpu, self.threads, self.os, self.arch, self.version
+        )
+    }
+}
+
+// What a report keeps of a game.
+#[derive(Clone)]
+pub struct GameSummary {
+    pub x: u32,
+    pub o: u32,
+    pub outcome: char, // x, o or d for a draw
+    pub termination: Termination,
+    pub seconds: f64,
+    pub nodes: u64,
+    pub moves: String,
+}
+
+impl GameSummary {
+    pub fn new(record: &GameRecord) -> Self {
+        let score = record.score();
+        Self {
+            x: score.x(),
+            o: score.o(),
+            outcome: match score.outcome() {
+                Outcome::Xwin => 'x',
+                Outcome::OWin => 'o',
+                Outcome::Draw => 'd',
+            },
+            termination: score.termination(),
+            seconds: record.time().as_secs_f64(),
+            nodes: record.nodes(),
+            moves: transcript::to_transcript(record),
+        }
+    }
+}
+
+pub struct Matchup {
+    pub stat: Stat,
+    pub games: Vec<GameSummary>,
+}
+
+// The results of a benchmark run, written as JSON or as CSV with a row per game.
+pub struct Report {
+    pub machine: Fingerprint,
+    pub seed: u64,
+    pub threads: usize,
+    pub matchups: Vec<Matchup>,
+}
+
+impl Report {
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+        let m = &self.machine;
+        let _ = write!(
+            json,
+            "{{\"machine\":{{\"cpu\":{},\"threads\":{},\"os\":{},\"arch\":{},\"version\":{}}},",
+            json_string(&m.cpu),
+            m.threads,
+            json_string(&m.os),
+            json_string(&m.arch),
+            json_string(&m.version)
+        );
+        let _ = write!(
+            json,
+            "\"seed\":{},\"threads\":{},\"matchups\":[",
+            self.seed, self.threads
+        );
+        for (idx, matchup) in self.matchups.iter().enumerate() {
+            let stat = &matchup.stat;
+            let elo = stat.elo();
+            let _ = write!(
+                json,
+                "{}{{\"description\":{},\"games\":{},\"x_wins\":{},\"draws\":{},\"o_wins\":{},\
+                 \"timeouts\":{},\"forfeits\":{},\"seconds\":{},\"nodes\":{},\"elo\":{},\"los\":{},\"records\":[",
+                if idx == 0 { "" } else { "," },
+                json_string(stat.description()),
+                stat.iterations(),
+                stat.x_wins(),
+                stat.draws(),
+                stat.o_wins(),
+                stat.timeouts(),
+                stat.forfeits(),
+                stat.duration().as_secs_f64(),
+                stat.nodes(),
+                json_number(elo.elo),
+                json_number(elo.los)
+            );
+            for (game, summary) in matchup.games.iter().enumerate() {
+                let _ = write!(
+                    json,
+                    "{}{{\"x\":{},\"o\":{},\"outcome\":\"{}\",\"termination\":{},\"seconds\":{},\
+                     \"nodes\":{},\"moves\":\"{}\"}}",
+                    if game == 0 { "" } else { "," },
+                    summary.x,
+                    summary.o,
+                    summary.outcome,
+                    json_string(&summary.termination.to_string()),
+                    summary.seconds,
+                    summary.nodes,
+                    summary.moves
+                );
+            }
+            json.push_str("]}");
+        }
+        json.push_str("]}\n");
+        json
+    }
+
+    // The machine on a comment line, then a header and a row per game.
+    pub fn to_csv(&self) -> String {
+        let mut csv = format!("# machine: {}\n{CSV_HEADER}\n", self.machine);
+        for matchup in &self.matchups {
+            let description = csv_field(matchup.stat.description());
+            for (game, summary) in matchup.games.iter().enumerate() {
+                let _ = writeln!(
+                    csv,
+                    "{description},{game},{},{},{},{},{},{},{}",
+                    summary.x,
+                    summary.o,
+                    summary.outcome,
+                    csv_field(&summary.termination.to_string()),
+                    summary.seconds,
+                    summary.nodes,
+                    summary.moves
+                );
+            }
+        }
+        csv
+    }
+}
+
+const CSV_HEADER: &str = "matchup,game,x,o,outcome,termination,seconds,nodes,moves";
+
+fn json_string(text: &str) -> String {
+    let mut escaped = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if c.is_control() => {
+                let _ = write!(escaped, "\\u{:04x}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+// JSON has no infinity, an Elo from all wins or all losses is written as null.
+fn json_number(value: f64) -> String {
+    if value.is_finite() {
+        value.to_string()
+    } else {
+        "null".to_string()
+    }
+}
+
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+// A matchup's totals read back from a CSV report.
+#[derive(Clone, Debug)]
+pub struct Totals {
+    pub matchup: String,
+    pub games: u32,
+    pub x_points: f64,
+    pub seconds: f64,
+    pub nodes: u64,
+}
+
+impl Totals {
+    // X's share of the points, from 0 to 1.
+    pub fn score(&self) -> f64 {
+        self.x_points / self.games.max(1) as f64
+    }
+
+    pub fn seconds_per_game(&self) -> f64 {
+        self.seconds / self.games.max(1) as f64
+    }
+}
+
+// Reads a CSV report as the machine it ran on and the totals of each matchup, in order.
+pub fn read_csv(text: &str) -> io::Result<(Option<String>, Vec<Totals>)> {
+    let invalid = |line: &str| invalid_data(format!("invalid row: {line}"));
+    let mut machine = None;
+    let mut totals: Vec<Totals> = Vec::new();
+    for line in text.lines().filter(|line| !line.trim().is_empty()) {
+        if let Some(comment) = line.strip_prefix('#') {
+            if let Some(name) = comment.trim().strip_prefix("machine:") {
+                machine = Some(name.trim().to_string());
+            }
+            continue;
+        }
+        if line == CSV_HEADER {
+            continue;
+        }
+        let fields = split_csv(line);
+        let [matchup, _, _, _, outcome, _, seconds, nodes, _] = &fields[..] else {
+            return Err(invalid(line));
+        };
+        let points = match outcome.as_str() {
+            "x" => 1.0,
+            "d" => 0.5,
+            "o" => 0.0,
+            _ => return Err(invalid(line)),
+        };
+        let seconds: f64 = seconds.parse().map_err(|_| invalid(line))?;
+        let nodes: u64 = nodes.parse().map_err(|_| invalid(line))?;
+
+        let idx = match totals.iter().position(|t| &t.matchup == matchup) {
+            Some(idx) => idx,
+            None => {
+                totals.push(Totals {
+                    matchup: matchup.clone(),
+                    games: 0,
+                    x_points: 0.0,
+                    seconds: 0.0,
+                    nodes: 0,
+                });
+                totals.len() - 1
+            }
+        };
+        let total = &mut totals[idx];
+        total.games += 1;
+        total.x_points += points;
+        total.seconds += seconds;
+        total.nodes += nodes;
+    }
+    Ok((machine, totals))
+}
+
+fn split_csv(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                fields.last_mut().unwrap().push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(String::new()),
+            c => fields.last_mut().unwrap().push(c),
+        }
+    }
+    fields
+}
+
+// How a matchup changed from the baseline to a new run. A regression is X's score dropping by
+// more than score_threshold percentage points, or games taking more than speed_threshold percent
+// longer.
+pub struct Comparison {
+    pub matchup: String,
+    pub score_change: f64, // percentage points
+    pub time_change: f64,  // percent
+    pub score_regression: bool,
+    pub speed_regression: bool,
+}
+
+pub fn compare(
+    baseline: &[Totals],
+    run: &[Totals],
+    score_threshold: f64,
+    speed_threshold: f64,
+) -> Vec<Comparison> {
+    run.iter()
+        .filter_map(|new| {
+            let old = baseline.iter().find(|old| old.matchup == new.matchup)?;
+            let score_change = 100.0 * (new.score() - old.score());
+            let time_change = if old.seconds_per_game() > 0.0 {
+                100.0 * (new.seconds_per_game() / old.seconds_per_game() - 1.0)
+            } else {
+                0.0
+            };
+            Some(Comparison {
+                matchup: new.matchup.clone(),
+                score_change,
+                time_change,
+                score_regression: score_change < -score_threshold,
+                speed_regression: time_change > speed_threshold,
+            })
+        })
+        .collect()
+}
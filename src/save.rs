@@ -0,0 +1,256 @@
+use crate::board::Board;
+use crate::clock::Clock;
+use crate::game::{GameRecord, Ply, Score};
+use crate::player::PlayerSpec;
+use crate::utils::invalid_data;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+// An unfinished game with its players, so it can be resumed later. Saved as text:
+//
+// x human
+// o negamax 10 good
+// start ---------------------------xo------ox--------------------------- x
+// moves f5 d6 pass c3
+// position <board> <side to move>
+// time 300+5
+// clock 241.5 290
+//
+// Boards are written as in Board::to_compact. The position is checked against the moves on loading.
+// Games with a time control add it and the seconds x and o have left, untimed games leave them out.
+pub struct SavedGame {
+    pub x: PlayerSpec,
+    pub o: PlayerSpec,
+    pub record: GameRecord,
+    pub clock: Option<Clock>,
+}
+
+impl SavedGame {
+    pub const fn new(x: PlayerSpec, o: PlayerSpec, record: GameRecord) -> Self {
+        Self {
+            x,
+            o,
+            record,
+            clock: None,
+        }
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+
+    pub fn parse(text: &str) -> io::Result<Self> {
+        let mut x = None;
+        let mut o = None;
+        let mut start = None;
+        let mut moves = Vec::new();
+        let mut position = None;
+        let mut time_control = None;
+        let mut remaining = None;
+
+        for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            match key {
+                "x" => x = Some(PlayerSpec::parse(value).map_err(invalid_data)?),
+                "o" => o = Some(PlayerSpec::parse(value).map_err(invalid_data)?),
+                "start" => start = Some(parse_position(value)?),
+                "position" => position = Some(parse_position(value)?),
+                "time" => time_control = Some(value.parse().map_err(invalid_data)?),
+                "clock" => remaining = Some(parse_clock(value)?),
+                "moves" => {
+                    for mv in value.split_whitespace() {
+                        moves.push(parse_ply(mv)?);
+                    }
+                }
+                _ => return Err(invalid_data(format!("unknown line: {line}"))),
+            }
+        }
+
+        let (Some(x), Some(o), Some((start, start_x_turn))) = (x, o, start) else {
+            return Err(invalid_data(
+                "missing players or start position".to_string(),
+            ));
+        };
+        let record = replay(start, start_x_turn, moves)?;
+        if let Some((board, x_turn)) = position {
+            let (end, end_x_turn) = record.positions().pop().unwrap();
+            if board.to_compact() != end.to_compact() || x_turn != end_x_turn {
+                return Err(invalid_data(
+                    "the position doesn't match the moves".to_string(),
+                ));
+            }
+        }
+        let clock = match (time_control, remaining) {
+            (Some(control), Some((x, o))) => Some(Clock::with_remaining(control, x, o)),
+            (Some(control), None) => Some(Clock::new(control)),
+            (None, Some(_)) => {
+                return Err(invalid_data("a clock without a time control".to_string()));
+            }
+            (None, None) => None,
+        };
+        Ok(Self {
+            x,
+            o,
+            record,
+            clock,
+        })
+    }
+}
+
+impl fmt::Display for SavedGame {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let side = |x_turn| if x_turn { 'x' } else { 'o' };
+        writeln!(f, "x {}", self.x)?;
+        writeln!(f, "o {}", self.o)?;
+        writeln!(
+            f,
+            "start {} {}",
+            self.record.start().to_compact(),
+            side(self.record.start_x_turn())
+        )?;
+        write!(f, "moves")?;
+        for ply in self.record.moves() {
+            write!(
+                f,
+                " {}",
+                ply.mv.map_or("pass".to_string(), Board::square_name)
+            )?;
+        }
+        writeln!(f)?;
+        let (board, x_turn) = self.record.positions().pop().unwrap();
+        writeln!(f, "position {} {}", board.to_compact(), side(x_turn))?;
+        if let Some(clock) = &self.clock {
+            writeln!(f, "time {}", clock.control())?;
+            writeln!(
+                f,
+                "clock {:.3} {:.3}",
+                clock.remaining(true).as_secs_f64(),
+                clock.remaining(false).as_secs_f64()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_position(value: &str) -> io::Result<(Board, bool)> {
+    Board::parse_position(value).ok_or_else(|| invalid_data(format!("invalid position: {value}")))
+}
+
+// The seconds x and o have left.
+fn parse_clock(value: &str) -> io::Result<(Duration, Duration)> {
+    let invalid = || invalid_data(format!("invalid clock: {value}"));
+    let seconds = |text: &str| {
+        text.parse()
+            .ok()
+            .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+            .ok_or_else(invalid)
+    };
+    let (x, o) = value.split_once(' ').ok_or_else(invalid)?;
+    Ok((seconds(x.trim())?, seconds(o.trim())?))
+}
+
+fn parse_ply(mv: &str) -> io::Result<Ply> {
+    if mv == "pass" {
+        return Ok(Ply::new(None));
+    }
+    let mv = Board::parse_square(mv).ok_or_else(|| invalid_data(format!("invalid move {mv}")))?;
+    Ok(Ply::new(Some(mv)))
+}
+
+// Passes are written out, so each one is checked like a move.
+fn replay(start: Board, start_x_turn: bool, moves: Vec<Ply>) -> io::Result<GameRecord> {
+    let mut board = start.clone();
+    let mut x_turn = start_x_turn;
+    for (idx, ply) in moves.iter().enumerate() {
+        let legal = board.legal_moves(x_turn);
+        match ply.mv {
+            Some(mv) if legal & mv != 0 => board.apply_move(mv, x_turn),
+            None if legal == 0 => {}
+            _ => return Err(invalid_data(format!("illegal move at ply {}", idx + 1))),
+        }
+        x_turn = !x_turn;
+    }
+    let score = Score::new(board.get_x().count_ones(), board.get_o().count_ones());
+    Ok(GameRecord::new(start, start_x_turn, moves, score))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Game;
+    use crate::player::RandomAI;
+    use crate::transcript;
+
+    fn saved_game() -> SavedGame {
+        let record = transcript::parse("f5d6c3d3c4").unwrap();
+        let mut game = SavedGame::new(
+            PlayerSpec::Human,
+            PlayerSpec::parse("negamax 10 good").unwrap(),
+            record,
+        );
+        game.clock = Some(Clock::with_remaining(
+            "300+5".parse().unwrap(),
+            Duration::from_millis(241_500),
+            Duration::from_secs(290),
+        ));
+        game
+    }
+
+    #[test]
+    fn round_trips_a_game_with_a_clock() {
+        let game = saved_game();
+        let loaded = SavedGame::parse(&game.to_string()).unwrap();
+        assert_eq!((&loaded.x, &loaded.o), (&game.x, &game.o));
+        assert_eq!(
+            transcript::to_transcript(&loaded.record),
+            transcript::to_transcript(&game.record)
+        );
+        let (clock, loaded_clock) = (game.clock.unwrap(), loaded.clock.unwrap());
+        assert_eq!(loaded_clock.control(), clock.control());
+        for x_turn in [true, false] {
+            assert_eq!(loaded_clock.remaining(x_turn), clock.remaining(x_turn));
+        }
+    }
+
+    #[test]
+    fn round_trips_passes() {
+        let record = (0..)
+            .map(|seed| {
+                Game::new(
+                    RandomAI::with_seed('x', seed),
+                    RandomAI::with_seed('o', seed + 1),
+                )
+                .run()
+            })
+            .find(|record| record.moves().iter().any(|ply| ply.mv.is_none()))
+            .unwrap();
+        let game = SavedGame::new(PlayerSpec::Human, PlayerSpec::Human, record);
+        let loaded = SavedGame::parse(&game.to_string()).unwrap();
+        let squares =
+            |record: &GameRecord| record.moves().iter().map(|ply| ply.mv).collect::<Vec<_>>();
+        assert_eq!(squares(&loaded.record), squares(&game.record));
+        assert!(loaded.clock.is_none());
+    }
+
+    #[test]
+    fn rejects_a_position_that_does_not_match_the_moves() {
+        let text = saved_game().to_string();
+        let moves = text.lines().find(|line| line.starts_with("moves")).unwrap();
+        let text = text.replace(moves, "moves f5 d6 c3");
+        assert!(SavedGame::parse(&text).is_err());
+    }
+
+    #[test]
+    fn rejects_a_clock_without_a_time_control() {
+        let text = saved_game().to_string();
+        let time = text.lines().find(|line| line.starts_with("time")).unwrap();
+        assert!(SavedGame::parse(&text.replace(time, "")).is_err());
+    }
+}
@@ -0,0 +1,199 @@
+use crate::board::Board;
+use crate::clock::TimeControl;
+use crate::game::Outcome;
+use crate::player::PlayerSpec;
+use crate::tournament::{Tally, play_game};
+use std::cmp::Ordering;
+use std::io;
+
+// A sequential probability ratio test of H0, the candidate is elo0 stronger than the baseline,
+// against H1, it is elo1 stronger. alpha and beta are the chances of accepting H1 when H0 holds
+// and H0 when H1 holds.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Sprt {
+    pub elo0: f64,
+    pub elo1: f64,
+    pub alpha: f64,
+    pub beta: f64,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Decision {
+    Continue,
+    AcceptH0,
+    AcceptH1,
+}
+
+impl Sprt {
+    pub const fn new(elo0: f64, elo1: f64, alpha: f64, beta: f64) -> Self {
+        Self {
+            elo0,
+            elo1,
+            alpha,
+            beta,
+        }
+    }
+
+    // The log likelihood ratios at which H0 and H1 are accepted.
+    pub fn bounds(&self) -> (f64, f64) {
+        (
+            (self.beta / (1.0 - self.alpha)).ln(),
+            ((1.0 - self.beta) / self.alpha).ln(),
+        )
+    }
+
+    // Log likelihood ratio of H1 over H0 for the candidate's results, using the normal
+    // approximation of the score distribution. Half a win and half a loss are added, so the
+    // variance isn't 0 while every game ended the same way.
+    pub fn llr(&self, tally: &Tally) -> f64 {
+        let games = tally.games() as f64 + 1.0;
+        let (w, d, l) = (
+            (tally.wins as f64 + 0.5) / games,
+            tally.draws as f64 / games,
+            (tally.losses as f64 + 0.5) / games,
+        );
+        let score = w + d / 2.0;
+        let variance = w * (1.0 - score).powi(2) + d * (0.5 - score).powi(2) + l * score.powi(2);
+        let (s0, s1) = (score_from_elo(self.elo0), score_from_elo(self.elo1));
+        games * (s1 - s0) * (2.0 * score - s0 - s1) / (2.0 * variance)
+    }
+
+    pub fn decide(&self, llr: f64) -> Decision {
+        let (lower, upper) = self.bounds();
+        if llr <= lower {
+            Decision::AcceptH0
+        } else if llr >= upper {
+            Decision::AcceptH1
+        } else {
+            Decision::Continue
+        }
+    }
+}
+
+fn score_from_elo(elo: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf(-elo / 400.0))
+}
+
+// Plays a candidate against a baseline in pairs of games from the same position with colours
+// swapped, until the test decides or max_pairs is reached. Pairs start from the positions in
+// turn, or from the usual start.
+pub struct SprtRun {
+    pub candidate: PlayerSpec,
+    pub baseline: PlayerSpec,
+    pub sprt: Sprt,
+    pub max_pairs: u32,
+    pub time_control: Option<TimeControl>,
+    pub positions: Vec<(Board, bool)>,
+}
+
+impl SprtRun {
+    pub const fn new(candidate: PlayerSpec, baseline: PlayerSpec, sprt: Sprt) -> Self {
+        Self {
+            candidate,
+            baseline,
+            sprt,
+            max_pairs: 10_000,
+            time_control: None,
+            positions: Vec::new(),
+        }
+    }
+
+    // Returns the decision and the candidate's results. on_pair gets the results and the LLR
+    // after every pair, to report them while the test runs.
+    pub fn run(&self, mut on_pair: impl FnMut(&Tally, f64)) -> io::Result<(Decision, Tally)> {
+        let mut tally = Tally::default();
+        for pair in 0..self.max_pairs as usize {
+            let (board, x_turn) = match self.positions.len() {
+                0 => (Board::new(), true),
+                len => self.positions[pair % len].clone(),
+            };
+            for candidate_x in [true, false] {
+                let (x, o) = if candidate_x {
+                    (&self.candidate, &self.baseline)
+                } else {
+                    (&self.baseline, &self.candidate)
+                };
+                let score = play_game(x, o, (&board, x_turn), self.time_control)?;
+                let (mine, theirs) = if candidate_x {
+                    (score.x(), score.o())
+                } else {
+                    (score.o(), score.x())
+                };
+                let result = match (score.outcome(), candidate_x) {
+                    (Outcome::Draw, _) => Ordering::Equal,
+                    (Outcome::Xwin, true) | (Outcome::OWin, false) => Ordering::Greater,
+                    _ => Ordering::Less,
+                };
+                tally.add(result, mine, theirs);
+            }
+
+            let llr = self.sprt.llr(&tally);
+            on_pair(&tally, llr);
+            let decision = self.sprt.decide(llr);
+            if decision != Decision::Continue {
+                return Ok((decision, tally));
+            }
+        }
+        Ok((Decision::Continue, tally))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tally(wins: u32, draws: u32, losses: u32) -> Tally {
+        Tally {
+            wins,
+            draws,
+            losses,
+            ..Tally::default()
+        }
+    }
+
+    #[test]
+    fn bounds_from_error_rates() {
+        let (lower, upper) = Sprt::new(0.0, 10.0, 0.05, 0.05).bounds();
+        assert!((lower + 19f64.ln()).abs() < 1e-12);
+        assert!((upper - 19f64.ln()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn known_llr() {
+        let sprt = Sprt::new(0.0, 10.0, 0.05, 0.05);
+        assert!((sprt.llr(&tally(600, 0, 400)) - 5.5625).abs() < 1e-4);
+        // An even score halfway between the hypotheses favours neither.
+        assert!(
+            Sprt::new(-5.0, 5.0, 0.05, 0.05)
+                .llr(&tally(40, 20, 40))
+                .abs()
+                < 1e-12
+        );
+    }
+
+    #[test]
+    fn llr_follows_the_results() {
+        let sprt = Sprt::new(0.0, 10.0, 0.05, 0.05);
+        assert!(sprt.llr(&tally(60, 20, 40)) > 0.0);
+        assert!(sprt.llr(&tally(40, 20, 60)) < 0.0);
+        // Every game won still gives a finite ratio.
+        assert!(sprt.llr(&tally(10, 0, 0)).is_finite());
+    }
+
+    #[test]
+    fn decides_at_the_bounds() {
+        let sprt = Sprt::new(0.0, 10.0, 0.05, 0.05);
+        assert_eq!(sprt.decide(0.0), Decision::Continue);
+        assert_eq!(sprt.decide(2.9), Decision::Continue);
+        assert_eq!(sprt.decide(3.0), Decision::AcceptH1);
+        assert_eq!(sprt.decide(-3.0), Decision::AcceptH0);
+        assert_eq!(
+            sprt.decide(sprt.llr(&tally(600, 0, 400))),
+            Decision::AcceptH1
+        );
+        assert_eq!(
+            sprt.decide(sprt.llr(&tally(400, 0, 600))),
+            Decision::AcceptH0
+        );
+    }
+}
@@ -0,0 +1,136 @@
+use crate::bench;
+use crate::board::Board;
+use crate::clock::TimeControl;
+use crate::openings;
+use crate::player::PlayerSpec;
+use crate::utils::invalid_data;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+// One benchmark of a suite: who plays x and o, how many games and how they start.
+// Without a seed the run's seed is used, without positions or openings the run's.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Matchup {
+    pub description: String,
+    pub x: PlayerSpec,
+    pub o: PlayerSpec,
+    pub games: u32,
+    pub seed: Option<u64>,
+    pub time_control: Option<TimeControl>,
+    pub positions: Option<String>,
+    pub openings: Option<String>,
+}
+
+impl Matchup {
+    pub fn new(description: impl Into<String>, x: PlayerSpec, o: PlayerSpec, games: u32) -> Self {
+        Self {
+            description: description.into(),
+            x,
+            o,
+            games,
+            seed: None,
+            time_control: None,
+            positions: None,
+            openings: None,
+        }
+    }
+
+    // The starting positions from the positions and openings files, empty without either.
+    pub fn read_positions(&self) -> io::Result<Vec<(Board, bool)>> {
+        let mut positions = Vec::new();
+        if let Some(path) = &self.positions {
+            positions.extend(bench::read_positions(path)?);
+        }
+        if let Some(path) = &self.openings {
+            positions.extend(openings::read_openings(path)?);
+        }
+        Ok(positions)
+    }
+}
+
+// A list of benchmarks, written as blocks of lines each starting with the description:
+//
+// matchup Nega 6s vs random
+// x negamax 6 simple
+// o random
+// games 100
+// seed 42
+// time 1+0.05
+// positions start.txt
+// openings openings.txt
+//
+// x, o and games are required, the lines after them optional. Lines starting with # are comments.
+pub fn parse(text: &str) -> io::Result<Vec<Matchup>> {
+    let mut matchups = Vec::new();
+    let mut current: Option<(String, Vec<(&str, &str)>)> = None;
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+        let value = value.trim();
+        if key == "matchup" {
+            if let Some((description, fields)) = current.take() {
+                matchups.push(build_matchup(description, &fields)?);
+            }
+            current = Some((value.to_string(), Vec::new()));
+        } else if let Some((_, fields)) = &mut current {
+            fields.push((key, value));
+        } else {
+            return Err(invalid_data(format!(
+                "expected a matchup line before {line}"
+            )));
+        }
+    }
+    if let Some((description, fields)) = current {
+        matchups.push(build_matchup(description, &fields)?);
+    }
+    Ok(matchups)
+}
+
+pub fn read_file(path: impl AsRef<Path>) -> io::Result<Vec<Matchup>> {
+    parse(&fs::read_to_string(path)?)
+}
+
+fn build_matchup(description: String, fields: &[(&str, &str)]) -> io::Result<Matchup> {
+    let error = |msg: String| invalid_data(format!("{description}: {msg}"));
+    let (mut x, mut o, mut games) = (None, None, None);
+    let mut matchup = Matchup::new(description.clone(), PlayerSpec::Human, PlayerSpec::Human, 0);
+    for &(key, value) in fields {
+        match key {
+            "x" => x = Some(PlayerSpec::parse(value).map_err(error)?),
+            "o" => o = Some(PlayerSpec::parse(value).map_err(error)?),
+            "games" => match number(key, value).map_err(error)? {
+                0 => return Err(error("games must be at least 1".to_string())),
+                count => games = Some(count),
+            },
+            "seed" => matchup.seed = Some(number(key, value).map_err(error)?),
+            "time" => matchup.time_control = Some(value.parse().map_err(error)?),
+            "positions" => matchup.positions = Some(value.to_string()),
+            "openings" => matchup.openings = Some(value.to_string()),
+            _ => return Err(error(format!("unknown line {key} {value}"))),
+        }
+    }
+    let (Some(x), Some(o), Some(games)) = (x, o, games) else {
+        return Err(error("needs x, o and games".to_string()));
+    };
+    matchup.x = x;
+    matchup.o = o;
+    matchup.games = games;
+    Ok(matchup)
+}
+
+fn number<T: FromStr>(key: &str, value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("invalid {key} {value}"))
+}
+
+// Matchups whose description contains the filter, ignoring case.
+pub fn filter(matchups: Vec<Matchup>, filter: &str) -> Vec<Matchup> {
+    let filter = filter.to_lowercase();
+    matchups
+        .into_iter()
+        .filter(|matchup| matchup.description.to_lowercase().contains(&filter))
+        .collect()
+}
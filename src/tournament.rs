@@ -0,0 +1,423 @@
+use crate::board::Board;
+use crate::clock::TimeControl;
+use crate::game::{Game, Outcome, Score, Termination, Violation};
+use crate::player::PlayerSpec;
+use crate::rating;
+use crate::utils::invalid_data;
+use std::cmp::Ordering;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+// A player taking part in a tournament, written as its name and spec like good negamax 6 good.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Entrant {
+    pub name: String,
+    pub spec: PlayerSpec,
+}
+
+impl Entrant {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+        let Some((name, spec)) = text.split_once(char::is_whitespace) else {
+            return Err(format!("expected a name and a player: {text}"));
+        };
+        Ok(Self {
+            name: name.to_string(),
+            spec: PlayerSpec::parse(spec)?,
+        })
+    }
+}
+
+impl fmt::Display for Entrant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.name, self.spec)
+    }
+}
+
+// Reads entrants, one per line. Empty lines and lines starting with # are skipped.
+// Names must be unique, results are kept by name.
+pub fn read_entrants(path: impl AsRef<Path>) -> io::Result<Vec<Entrant>> {
+    let entrants = fs::read_to_string(path)?
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| Entrant::parse(line).map_err(invalid_data))
+        .collect::<io::Result<Vec<_>>>()?;
+    check_unique(&entrants)?;
+    Ok(entrants)
+}
+
+fn check_unique(entrants: &[Entrant]) -> io::Result<()> {
+    for (idx, entrant) in entrants.iter().enumerate() {
+        if entrants[..idx].iter().any(|e| e.name == entrant.name) {
+            return Err(invalid_data(format!("duplicate entrant {}", entrant.name)));
+        }
+    }
+    Ok(())
+}
+
+// Who plays whom: everyone against everyone, or the first entrant against each of the others.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Format {
+    RoundRobin,
+    Gauntlet,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "round-robin" => Ok(Format::RoundRobin),
+            "gauntlet" => Ok(Format::Gauntlet),
+            _ => Err(format!("unknown tournament format {s}")),
+        }
+    }
+}
+
+// Every pairing plays the given number of pairs of games, the two games of a pair from the same
+// position with colours swapped. Pairs start from the positions in turn, or from the usual start.
+pub struct Tournament {
+    pub entrants: Vec<Entrant>,
+    pub format: Format,
+    pub pairs: u32,
+    pub time_control: Option<TimeControl>,
+    pub positions: Vec<(Board, bool)>,
+}
+
+impl Tournament {
+    pub const fn new(entrants: Vec<Entrant>, format: Format, pairs: u32) -> Self {
+        Self {
+            entrants,
+            format,
+            pairs,
+            time_control: None,
+            positions: Vec::new(),
+        }
+    }
+
+    // Indexes into entrants of the players meeting each other.
+    pub fn pairings(&self) -> Vec<(usize, usize)> {
+        let count = self.entrants.len();
+        match self.format {
+            Format::RoundRobin => (0..count)
+                .flat_map(|a| (a + 1..count).map(move |b| (a, b)))
+                .collect(),
+            Format::Gauntlet => (1..count).map(|b| (0, b)).collect(),
+        }
+    }
+
+    // Plays every game with freshly built players, handing the results so far to on_game
+    // after each one, for example to report progress or save them.
+    pub fn run(&self, mut on_game: impl FnMut(&Results)) -> io::Result<Results> {
+        let mut results = Results {
+            entrants: self.entrants.clone(),
+            games: Vec::new(),
+        };
+        for (a, b) in self.pairings() {
+            for pair in 0..self.pairs as usize {
+                let (board, x_turn) = match self.positions.len() {
+                    0 => (Board::new(), true),
+                    len => self.positions[pair % len].clone(),
+                };
+                for (x, o) in [(a, b), (b, a)] {
+                    let score = play_game(
+                        &self.entrants[x].spec,
+                        &self.entrants[o].spec,
+                        (&board, x_turn),
+                        self.time_control,
+                    )?;
+                    results.games.push(GameResult { x, o, score });
+                    on_game(&results);
+                }
+            }
+        }
+        Ok(results)
+    }
+}
+
+// Plays one game between freshly built players from the position.
+pub fn play_game(
+    x: &PlayerSpec,
+    o: &PlayerSpec,
+    (board, x_turn): (&Board, bool),
+    time_control: Option<TimeControl>,
+) -> io::Result<Score> {
+    let mut game = Game::from_position(board.clone(), x_turn, x.build('x')?, o.build('o')?);
+    game.set_time_control(time_control);
+    Ok(game.run().score().clone())
+}
+
+// One finished game, x and o being indexes into the entrants.
+#[derive(Clone)]
+pub struct GameResult {
+    pub x: usize,
+    pub o: usize,
+    pub score: Score,
+}
+
+// The games of a tournament, saved as text:
+//
+// entrant good negamax 6 good
+// entrant strategic negamax 6 strategic
+// game good strategic 40 24
+// game strategic good 20 30 timeout o
+// game good strategic 33 31 forfeit x f5
+//
+// Games name the x and o entrants, then give their discs and how the game ended if it wasn't
+// played out. A forfeit gives the illegal move, in hex if it isn't a single square, pass for a
+// pass with moves available or resigned.
+pub struct Results {
+    pub entrants: Vec<Entrant>,
+    pub games: Vec<GameResult>,
+}
+
+impl Results {
+    pub fn from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+
+    pub fn parse(text: &str) -> io::Result<Self> {
+        let mut entrants: Vec<Entrant> = Vec::new();
+        let mut games = Vec::new();
+        for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            match key {
+                "entrant" => {
+                    entrants.push(Entrant::parse(value).map_err(invalid_data)?);
+                    check_unique(&entrants)?;
+                }
+                "game" => games.push(parse_game(value, &entrants)?),
+                _ => return Err(invalid_data(format!("unknown line: {line}"))),
+            }
+        }
+        Ok(Self { entrants, games })
+    }
+
+    // BayesElo-style ratings of the entrants, averaging 0.
+    pub fn ratings(&self) -> Vec<f64> {
+        let games: Vec<(usize, usize, f64)> = self
+            .games
+            .iter()
+            .map(|game| {
+                let score = match game.score.outcome() {
+                    Outcome::Xwin => 1.0,
+                    Outcome::Draw => 0.5,
+                    Outcome::OWin => 0.0,
+                };
+                (game.x, game.o, score)
+            })
+            .collect();
+        rating::pool_ratings(self.entrants.len(), &games)
+    }
+
+    pub fn cross_table(&self) -> CrossTable {
+        let count = self.entrants.len();
+        let mut cells = vec![vec![Tally::default(); count]; count];
+        for game in &self.games {
+            let (x, o) = (game.score.x(), game.score.o());
+            let x_result = match game.score.outcome() {
+                Outcome::Xwin => Ordering::Greater,
+                Outcome::Draw => Ordering::Equal,
+                Outcome::OWin => Ordering::Less,
+            };
+            cells[game.x][game.o].add(x_result, x, o);
+            cells[game.o][game.x].add(x_result.reverse(), o, x);
+        }
+        CrossTable {
+            names: self.entrants.iter().map(|e| e.name.clone()).collect(),
+            cells,
+        }
+    }
+}
+
+impl fmt::Display for Results {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for entrant in &self.entrants {
+            writeln!(f, "entrant {entrant}")?;
+        }
+        for game in &self.games {
+            let name = |idx: usize| &self.entrants[idx].name;
+            write!(
+                f,
+                "game {} {} {} {}",
+                name(game.x),
+                name(game.o),
+                game.score.x(),
+                game.score.o()
+            )?;
+            match game.score.termination() {
+                Termination::Finished => {}
+                Termination::Timeout(symbol) => write!(f, " timeout {symbol}")?,
+                Termination::Forfeit(symbol, Violation::IllegalPass) => {
+                    write!(f, " forfeit {symbol} pass")?
+                }
+                Termination::Forfeit(symbol, Violation::Resigned) => {
+                    write!(f, " forfeit {symbol} resigned")?
+                }
+                Termination::Forfeit(symbol, Violation::IllegalMove(mv))
+                    if mv.count_ones() == 1 =>
+                {
+                    write!(f, " forfeit {symbol} {}", Board::square_name(mv))?
+                }
+                Termination::Forfeit(symbol, Violation::IllegalMove(mv)) => {
+                    write!(f, " forfeit {symbol} {mv:#x}")?
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_game(value: &str, entrants: &[Entrant]) -> io::Result<GameResult> {
+    let invalid = || invalid_data(format!("invalid game: {value}"));
+    let fields: Vec<&str> = value.split_whitespace().collect();
+    let entrant = |name: &str| {
+        entrants
+            .iter()
+            .position(|e| e.name == name)
+            .ok_or_else(|| invalid_data(format!("unknown entrant {name}")))
+    };
+    let symbol = |text: &str| match text {
+        "x" => Ok('x'),
+        "o" => Ok('o'),
+        _ => Err(invalid()),
+    };
+    let termination = match fields.get(4..) {
+        Some([]) => Termination::Finished,
+        Some(["timeout", side]) => Termination::Timeout(symbol(side)?),
+        Some(["forfeit", side, "pass"]) => {
+            Termination::Forfeit(symbol(side)?, Violation::IllegalPass)
+        }
+        Some(["forfeit", side, "resigned"]) => {
+            Termination::Forfeit(symbol(side)?, Violation::Resigned)
+        }
+        Some(["forfeit", side, mv]) => {
+            let mv = match mv.strip_prefix("0x") {
+                Some(hex) => u64::from_str_radix(hex, 16).ok(),
+                None => Board::parse_square(mv),
+            }
+            .ok_or_else(invalid)?;
+            Termination::Forfeit(symbol(side)?, Violation::IllegalMove(mv))
+        }
+        _ => return Err(invalid()),
+    };
+    let discs = |text: &str| text.parse().map_err(|_| invalid());
+    Ok(GameResult {
+        x: entrant(fields[0])?,
+        o: entrant(fields[1])?,
+        score: Score::with_termination(discs(fields[2])?, discs(fields[3])?, termination),
+    })
+}
+
+// Results of one entrant against another, or against everyone.
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct Tally {
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    pub discs_for: u32,
+    pub discs_against: u32,
+}
+
+impl Tally {
+    // Counts a game, result being Greater for a win of this side.
+    pub fn add(&mut self, result: Ordering, discs_for: u32, discs_against: u32) {
+        match result {
+            Ordering::Greater => self.wins += 1,
+            Ordering::Equal => self.draws += 1,
+            Ordering::Less => self.losses += 1,
+        }
+        self.discs_for += discs_for;
+        self.discs_against += discs_against;
+    }
+
+    pub const fn games(&self) -> u32 {
+        self.wins + self.draws + self.losses
+    }
+
+    // A point for a win and half a point for a draw.
+    pub fn score(&self) -> f64 {
+        self.wins as f64 + self.draws as f64 / 2.0
+    }
+
+    fn merge(&mut self, other: &Tally) {
+        self.wins += other.wins;
+        self.draws += other.draws;
+        self.losses += other.losses;
+        self.discs_for += other.discs_for;
+        self.discs_against += other.discs_against;
+    }
+}
+
+// Each entrant's results against each other entrant, printed with their totals,
+// best score first.
+pub struct CrossTable {
+    names: Vec<String>,
+    cells: Vec<Vec<Tally>>,
+}
+
+impl CrossTable {
+    // How a did against b.
+    pub fn get(&self, a: usize, b: usize) -> &Tally {
+        &self.cells[a][b]
+    }
+
+    pub fn total(&self, a: usize) -> Tally {
+        let mut total = Tally::default();
+        for cell in &self.cells[a] {
+            total.merge(cell);
+        }
+        total
+    }
+}
+
+impl fmt::Display for CrossTable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let width = self.names.iter().map(String::len).max().unwrap_or(0).max(4);
+        let mut order: Vec<usize> = (0..self.names.len()).collect();
+        order.sort_by(|&a, &b| self.total(b).score().total_cmp(&self.total(a).score()));
+
+        write!(f, "{:<width$}", "")?;
+        for &b in &order {
+            write!(f, " | {:^11.11}", self.names[b])?;
+        }
+        writeln!(
+            f,
+            " | {:^5} {:^5} {:^5} | {:^13} | {:^6}",
+            "W", "D", "L", "discs", "score"
+        )?;
+        for &a in &order {
+            write!(f, "{:<width$}", self.names[a])?;
+            for &b in &order {
+                let cell = self.get(a, b);
+                if a == b {
+                    write!(f, " | {:^11}", "-")?;
+                } else {
+                    let wdl = format!("{}-{}-{}", cell.wins, cell.draws, cell.losses);
+                    write!(f, " | {wdl:^11}")?;
+                }
+            }
+            let total = self.total(a);
+            writeln!(
+                f,
+                " | {:>5} {:>5} {:>5} | {:>6}:{:<6} | {:>6.1}",
+                total.wins,
+                total.draws,
+                total.losses,
+                total.discs_for,
+                total.discs_against,
+                total.score()
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,135 @@
+use crate::board::Board;
+use crate::game::{GameRecord, Ply, Score};
+use std::fmt;
+
+// A game written as its squares one after another, like f5d6c3d3c4. Passes are left out
+// and inferred when the side to move has no legal move.
+
+// Where a transcript stops making sense, index counts the moves in the transcript from 0.
+#[derive(Debug)]
+pub enum TranscriptError {
+    InvalidSquare { index: usize, text: String },
+    IllegalMove { index: usize, square: String },
+}
+
+impl fmt::Display for TranscriptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TranscriptError::InvalidSquare { index, text } => {
+                write!(f, "move {}: {text} is not a square", index + 1)
+            }
+            TranscriptError::IllegalMove { index, square } => {
+                write!(f, "move {}: {square} is illegal", index + 1)
+            }
+        }
+    }
+}
+
+// Splits the transcript into squares. Case and whitespace between moves don't matter.
+pub fn parse_moves(text: &str) -> Result<Vec<u64>, TranscriptError> {
+    let chars: Vec<char> = text.chars().filter(|c| !c.is_whitespace()).collect();
+    chars
+        .chunks(2)
+        .enumerate()
+        .map(|(index, pair)| {
+            let text: String = pair.iter().collect();
+            Board::parse_square(&text).ok_or(TranscriptError::InvalidSquare { index, text })
+        })
+        .collect()
+}
+
+// Plays the moves from a position, adding a pass whenever the side to move has no move.
+pub fn replay(start: &Board, x_turn: bool, moves: &[u64]) -> Result<GameRecord, TranscriptError> {
+    let mut board = start.clone();
+    let mut x_turn_now = x_turn;
+    let mut plies = Vec::new();
+
+    for (index, &mv) in moves.iter().enumerate() {
+        if board.num_moves(x_turn_now) == 0 {
+            plies.push(Ply::new(None));
+            x_turn_now = !x_turn_now;
+        }
+        if board.legal_moves(x_turn_now) & mv == 0 {
+            return Err(TranscriptError::IllegalMove {
+                index,
+                square: Board::square_name(mv),
+            });
+        }
+        board.apply_move(mv, x_turn_now);
+        plies.push(Ply::new(Some(mv)));
+        x_turn_now = !x_turn_now;
+    }
+
+    // A pass after the last move belongs to the position the transcript sets up.
+    if board.num_moves(x_turn_now) == 0 && !board.is_over() {
+        plies.push(Ply::new(None));
+    }
+
+    let score = Score::new(board.get_x().count_ones(), board.get_o().count_ones());
+    Ok(GameRecord::new(start.clone(), x_turn, plies, score))
+}
+
+// A game from the usual start position.
+pub fn parse(text: &str) -> Result<GameRecord, TranscriptError> {
+    replay(&Board::new(), true, &parse_moves(text)?)
+}
+
+pub fn to_transcript(record: &GameRecord) -> String {
+    record
+        .moves()
+        .iter()
+        .filter_map(|ply| ply.mv)
+        .map(Board::square_name)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Game;
+    use crate::player::RandomAI;
+
+    fn squares(record: &GameRecord) -> Vec<Option<u64>> {
+        record.moves().iter().map(|ply| ply.mv).collect()
+    }
+
+    #[test]
+    fn round_trips_games_with_passes() {
+        let mut passes = 0;
+        for seed in 0..20 {
+            let record = Game::new(
+                RandomAI::with_seed('x', seed),
+                RandomAI::with_seed('o', seed + 1),
+            )
+            .run();
+            passes += squares(&record).contains(&None) as u32;
+            let text = to_transcript(&record);
+            let parsed = parse(&text).unwrap();
+            assert_eq!(squares(&parsed), squares(&record), "{text}");
+            assert_eq!(
+                (parsed.score().x(), parsed.score().o()),
+                (record.score().x(), record.score().o()),
+                "{text}"
+            );
+        }
+        assert!(passes > 0, "no game had a pass");
+    }
+
+    #[test]
+    fn ignores_case_and_whitespace() {
+        let record = parse("F5 d6\nC3").unwrap();
+        assert_eq!(to_transcript(&record), "f5d6c3");
+    }
+
+    #[test]
+    fn reports_where_the_transcript_goes_wrong() {
+        assert!(matches!(
+            parse("f5z9"),
+            Err(TranscriptError::InvalidSquare { index: 1, .. })
+        ));
+        assert!(matches!(
+            parse("f5f5"),
+            Err(TranscriptError::IllegalMove { index: 1, .. })
+        ));
+    }
+}
@@ -1,3 +1,5 @@
+use std::io;
+
 const RESET: &str = "\x1b[0m";
 const LIGHT_CYAN: &str = "\x1b[96m";
 const LIGHT_MAGENTA: &str = "\x1b[95m";
@@ -13,3 +15,8 @@ pub const fn ansi_for(symbol: char) -> &'static str {
         _ => RESET,
     }
 }
+
+// The error for a file or text that can't be parsed.
+pub fn invalid_data(msg: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.into())
+}
@@ -0,0 +1,214 @@
+use crate::board::Board;
+use crate::game::GameRecord;
+use crate::transcript;
+use crate::utils::invalid_data;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const HEADER_SIZE: usize = 16;
+const GAME_SIZE: usize = 68;
+const PLAYER_NAME_SIZE: usize = 20;
+const TOURNAMENT_NAME_SIZE: usize = 26;
+
+// A game from a WTHOR database. Players and tournaments are numbers into the matching
+// .JOU and .TRN files, read with read_names. Black is x and the games start from the usual position.
+#[derive(Clone)]
+pub struct WthorGame {
+    pub tournament: u16,
+    pub black: u16,
+    pub white: u16,
+    pub year: u16,
+    // Discs black ended with, and with perfect play from the position theoretical_depth empties
+    // from the end.
+    pub black_score: u8,
+    pub theoretical_score: u8,
+    pub record: GameRecord,
+}
+
+// The contents of a .wtb file. Games whose moves do not replay are kept out of games and
+// listed in rejected with their position in the file and the reason.
+pub struct WthorDatabase {
+    pub created: (u16, u8, u8), // year, month, day
+    pub year: u16,
+    pub theoretical_depth: u8,
+    pub games: Vec<WthorGame>,
+    pub rejected: Vec<(usize, String)>,
+}
+
+impl WthorDatabase {
+    pub fn from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::parse(&fs::read(path)?)
+    }
+
+    // File layout, little endian: creation century, year, month and day (u8 each), number of games
+    // (u32), number of names (u16, used by name files), year of the games (u16), board size (u8,
+    // 0 or 8), game type (u8), depth of the theoretical score (u8), a reserved byte, then the games.
+    // Each game is tournament, black and white numbers (u16 each), black's score and theoretical
+    // score (u8 each) and 60 moves written as 10 * rank + file, with 0 after the last move.
+    pub fn parse(bytes: &[u8]) -> io::Result<Self> {
+        let Some((header, body)) = bytes.split_first_chunk::<HEADER_SIZE>() else {
+            return Err(invalid_data(
+                "file too short for a WTHOR header".to_string(),
+            ));
+        };
+        let count = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as usize;
+        let year = u16::from_le_bytes([header[10], header[11]]);
+        if header[12] != 0 && header[12] != 8 {
+            return Err(invalid_data(format!(
+                "unsupported board size {}",
+                header[12]
+            )));
+        }
+        if body.len() < count * GAME_SIZE {
+            return Err(invalid_data(format!(
+                "header lists {count} games but the file holds {}",
+                body.len() / GAME_SIZE
+            )));
+        }
+
+        let mut database = Self {
+            created: (
+                header[0] as u16 * 100 + header[1] as u16,
+                header[2],
+                header[3],
+            ),
+            year,
+            theoretical_depth: header[14],
+            games: Vec::with_capacity(count),
+            rejected: Vec::new(),
+        };
+        for (idx, game) in body.chunks_exact(GAME_SIZE).take(count).enumerate() {
+            match parse_game(game, year) {
+                Ok(game) => database.games.push(game),
+                Err(err) => database.rejected.push((idx, err)),
+            }
+        }
+        Ok(database)
+    }
+}
+
+fn parse_game(bytes: &[u8], year: u16) -> Result<WthorGame, String> {
+    let word = |i: usize| u16::from_le_bytes([bytes[i], bytes[i + 1]]);
+    let mut moves = Vec::new();
+    for &code in bytes[8..].iter().take_while(|&&code| code != 0) {
+        let (rank, file) = (code / 10, code % 10);
+        if !(1..=8).contains(&rank) || !(1..=8).contains(&file) {
+            return Err(format!("invalid move code {code}"));
+        }
+        moves.push(Board::get_move(8 - rank, file - 1));
+    }
+
+    // Passes are not stored, the transcript replay adds them back.
+    let record = transcript::replay(&Board::new(), true, &moves).map_err(|err| err.to_string())?;
+    Ok(WthorGame {
+        tournament: word(0),
+        black: word(2),
+        white: word(4),
+        year,
+        black_score: bytes[6],
+        theoretical_score: bytes[7],
+        record,
+    })
+}
+
+// Reads the player (.JOU) or tournament (.TRN) names, indexed by the numbers in the games.
+// Names are zero padded Latin-1 after the same 16 byte header as the games file.
+pub fn read_names(path: impl AsRef<Path>) -> io::Result<Vec<String>> {
+    let path = path.as_ref();
+    let size = match path.extension().and_then(|e| e.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case("jou") => PLAYER_NAME_SIZE,
+        Some(ext) if ext.eq_ignore_ascii_case("trn") => TOURNAMENT_NAME_SIZE,
+        _ => return Err(invalid_data("expected a .JOU or .TRN file".to_string())),
+    };
+    let bytes = fs::read(path)?;
+    let Some((header, body)) = bytes.split_first_chunk::<HEADER_SIZE>() else {
+        return Err(invalid_data(
+            "file too short for a WTHOR header".to_string(),
+        ));
+    };
+    let count = u16::from_le_bytes([header[8], header[9]]) as usize;
+    Ok(body
+        .chunks_exact(size)
+        .take(count)
+        .map(|name| {
+            let end = name.iter().position(|&b| b == 0).unwrap_or(name.len());
+            name[..end]
+                .iter()
+                .map(|&b| b as char)
+                .collect::<String>()
+                .trim_end()
+                .to_string()
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A header for the given games, created 2024-03-09 for games of 2023.
+    fn database(games: &[[u8; GAME_SIZE]]) -> Vec<u8> {
+        let mut bytes = vec![20, 24, 3, 9];
+        bytes.extend((games.len() as u32).to_le_bytes());
+        bytes.extend(0u16.to_le_bytes());
+        bytes.extend(2023u16.to_le_bytes());
+        bytes.extend([8, 0, 22, 0]);
+        for game in games {
+            bytes.extend(game);
+        }
+        bytes
+    }
+
+    fn game(tournament: u16, black: u16, white: u16, moves: &[u8]) -> [u8; GAME_SIZE] {
+        let mut game = [0; GAME_SIZE];
+        game[0..2].copy_from_slice(&tournament.to_le_bytes());
+        game[2..4].copy_from_slice(&black.to_le_bytes());
+        game[4..6].copy_from_slice(&white.to_le_bytes());
+        game[6] = 36;
+        game[7] = 34;
+        game[8..8 + moves.len()].copy_from_slice(moves);
+        game
+    }
+
+    #[test]
+    fn reads_games_and_header() {
+        // f5 d6 c3, then a game with a square off the board.
+        let bytes = database(&[game(7, 12, 345, &[56, 64, 33]), game(1, 2, 3, &[56, 99])]);
+        let database = WthorDatabase::parse(&bytes).unwrap();
+        assert_eq!(database.created, (2024, 3, 9));
+        assert_eq!(database.year, 2023);
+        assert_eq!(database.theoretical_depth, 22);
+
+        let [game] = &database.games[..] else {
+            panic!("expected one game");
+        };
+        assert_eq!(
+            (game.tournament, game.black, game.white, game.year),
+            (7, 12, 345, 2023)
+        );
+        assert_eq!((game.black_score, game.theoretical_score), (36, 34));
+        assert_eq!(transcript::to_transcript(&game.record), "f5d6c3");
+        assert_eq!(
+            database.rejected,
+            vec![(1, "invalid move code 99".to_string())]
+        );
+    }
+
+    #[test]
+    fn rejects_games_that_do_not_replay() {
+        // f5 twice.
+        let bytes = database(&[game(0, 0, 0, &[56, 56])]);
+        let database = WthorDatabase::parse(&bytes).unwrap();
+        assert!(database.games.is_empty());
+        assert_eq!(database.rejected.len(), 1);
+    }
+
+    #[test]
+    fn rejects_truncated_files() {
+        assert!(WthorDatabase::parse(&[20, 24, 3]).is_err());
+        let mut bytes = database(&[game(0, 0, 0, &[56])]);
+        bytes.truncate(bytes.len() - 1);
+        assert!(WthorDatabase::parse(&bytes).is_err());
+    }
+}